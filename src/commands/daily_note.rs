@@ -3,18 +3,55 @@ use std::{fs, path::PathBuf, process::Command};
 use chrono::NaiveDate;
 
 use crate::{
-    config::Config,
+    commands::{
+        scheduled::{save_scheduled_tasks, take_scheduled_tasks},
+        task::add_section_task,
+    },
+    config::{CarryOver, Config},
+    note_store::NoteStore,
     utils::{
-        markdown::{MarkdownBlock, MarkdownFile},
-        time::get_today_date,
+        markdown::{MarkdownFile, TaskRef, checklist::ChecklistItem},
+        schedule::Schedule,
+        template::{TemplateContext, render_template},
     },
 };
 
 // todo: eventually, it'd be nice to have a struct/impl like "DailyNote"
 
-pub fn open_daily_note(config: Config, create_fresh: bool) -> Result<String, String> {
-    let daily_note_path = get_daily_note_path();
-    create_daily_note_if_not_exists(&daily_note_path, create_fresh)?;
+/// Name of the template written out when none exists yet
+pub const DEFAULT_TEMPLATE_NAME: &str = "daily";
+
+const DEFAULT_TEMPLATE: &str = "# {{DATE}}
+
+## Tasks
+
+### Priority
+
+### Support
+
+### Project Management
+
+### Engineering
+
+### Intake
+
+## Notes
+";
+
+pub fn open_daily_note(
+    config: Config,
+    date: NaiveDate,
+    create_fresh: bool,
+    template: Option<&str>,
+) -> Result<String, String> {
+    let daily_note_path = get_daily_note_path(date);
+    if template.is_some() && daily_note_path.exists() {
+        eprintln!(
+            "The {} note already exists, so --template has no effect",
+            date
+        );
+    }
+    create_daily_note(&config, date, create_fresh, template)?;
 
     let mut daily_note_cmd = Command::new(&config.editor_command);
     daily_note_cmd.arg(daily_note_path);
@@ -28,36 +65,35 @@ pub fn open_daily_note(config: Config, create_fresh: bool) -> Result<String, Str
             status.code()
         ));
     }
-    return Ok("Success".to_string());
+    Ok("Success".to_string())
 }
 
 pub fn create_daily_note_if_not_exists(
-    daily_note_path: &PathBuf,
+    config: &Config,
+    date: NaiveDate,
     create_fresh: bool,
 ) -> Result<MarkdownFile, String> {
+    create_daily_note(config, date, create_fresh, None)
+}
+
+/// Reads the note for `date`, creating it if it doesn't exist yet: from the previous note
+/// without its finished tasks, or from the configured template when there is no previous
+/// note or `create_fresh` is set. Naming a `template`, a `template_rules` entry matching
+/// the date or `merge_into_template` starts from the template instead, with the previous
+/// note's open tasks merged in under the headings of the same name.
+pub fn create_daily_note(
+    config: &Config,
+    date: NaiveDate,
+    create_fresh: bool,
+    template: Option<&str>,
+) -> Result<MarkdownFile, String> {
+    let daily_note_path = get_daily_note_path(date);
     if daily_note_path.exists() {
         return MarkdownFile::from_path(&daily_note_path).map_err(|e| e.to_string());
     }
 
-    let last_note_path = get_last_daily_note_path();
-    let today = get_today_date();
-
-    let note_source = if create_fresh || last_note_path.is_err() {
-        from_template_file()?
-    } else {
-        let last_note_file = MarkdownFile::from_path(&last_note_path.unwrap())
-            .map_err(|e| format!("Failed to read last note: {}", e))?;
-        last_note_file.filter_completed_tasks().set_title(&today)
-    };
-
-    // Replace {{DATE}} with actual date
-    let mut note = note_source.clone();
-    // update note header by replacing {{ DATE }} with today's date
-    if let MarkdownBlock::Heading(header) = &mut note.blocks[0] {
-        let mut new_header = header.clone();
-        new_header.content = new_header.content.replace("{{DATE}}", &today);
-        note.blocks[0] = MarkdownBlock::Heading(new_header);
-    }
+    let new_note = build_daily_note(config, date, create_fresh, template)?;
+    write_default_template_if_missing()?;
 
     // Create parent directory if needed
     if let Some(parent) = daily_note_path.parent() {
@@ -65,14 +101,126 @@ pub fn create_daily_note_if_not_exists(
     }
 
     // Write file
-    fs::write(daily_note_path, note.to_string())
+    fs::write(daily_note_path, new_note.note.to_string())
         .map_err(|e| format!("Failed to write daily note: {}", e))?;
 
-    Ok(note)
+    // Only once the tasks are safely in the new note
+    if let Some(queue) = new_note.scheduled_queue {
+        save_scheduled_tasks(&queue)?;
+    }
+    if let Some((last_note_path, mut last_note_file, carried_tasks)) = new_note.migrate_from
+        && last_note_file.migrate_tasks(&carried_tasks) > 0
+    {
+        last_note_file
+            .save(&last_note_path)
+            .map_err(|e| format!("Failed to save {}: {}", last_note_path.display(), e))?;
+    }
+
+    Ok(new_note.note)
 }
 
-pub fn open_last_daily_note(config: Config) -> Result<String, String> {
-    let last_note_path = get_last_daily_note_path()?;
+/// Reads the note for `date`, or works out what it would be created as, without
+/// writing anything
+pub fn preview_daily_note(
+    config: &Config,
+    date: NaiveDate,
+    create_fresh: bool,
+) -> Result<MarkdownFile, String> {
+    let daily_note_path = get_daily_note_path(date);
+    if daily_note_path.exists() {
+        return MarkdownFile::from_path(&daily_note_path).map_err(|e| e.to_string());
+    }
+    build_daily_note(config, date, create_fresh, None).map(|new_note| new_note.note)
+}
+
+/// A daily note that hasn't been written yet
+struct NewDailyNote {
+    note: MarkdownFile,
+    /// The scheduled tasks left once this note's have been taken, to save after the note
+    scheduled_queue: Option<MarkdownFile>,
+    /// The previous note and its tasks that were carried into this one, to be marked
+    /// migrated once this note is saved
+    migrate_from: Option<(PathBuf, MarkdownFile, Vec<TaskRef>)>,
+}
+
+fn build_daily_note(
+    config: &Config,
+    date: NaiveDate,
+    create_fresh: bool,
+    template: Option<&str>,
+) -> Result<NewDailyNote, String> {
+    let title = date.to_string();
+    let template = match template {
+        Some(template) => Some(template),
+        None => template_for_date(config, date)?.or(config
+            .merge_into_template
+            .then_some(config.template.as_str())),
+    };
+
+    // Carry over from the note just before this one, so back-filling a past day doesn't
+    // pull in tasks from later notes
+    let previous_note = NoteStore::from_home().latest_before(date)?;
+    let mut context = TemplateContext::new(date, previous_note.as_ref().map(|(date, _)| *date));
+    context.custom = config.template_variables.clone();
+    let last_note = previous_note.filter(|_| !create_fresh);
+    let mut migrate_from = None;
+    let mut note = match last_note {
+        Some((last_date, last_note_path)) => {
+            let last_note_file = MarkdownFile::from_path(&last_note_path)
+                .map_err(|e| format!("Failed to read last note: {}", e))?;
+            // Carried tasks remember the day they were first written down, so they can be
+            // flagged once they've been rolled forward for too long
+            let mut carried = last_note_file.clone();
+            carried.mark_tasks_since(last_date);
+            let (note, carried_tasks) = match template {
+                Some(template) => {
+                    let mut note = load_template(template, &context)?;
+                    let merged = note.merge_tasks(&carried);
+                    for heading in merged.headings {
+                        eprintln!(
+                            "Warning: template '{}' has no '{}' section, so it was added for \
+                             tasks carried over from {}",
+                            template, heading, last_date
+                        );
+                    }
+                    let carried_tasks = merged.tasks.into_iter().chain(merged.already_open);
+                    (note, carried_tasks.collect())
+                }
+                None => {
+                    let top_level = carried
+                        .tasks()
+                        .into_iter()
+                        .filter(|(task_ref, _)| task_ref.path.len() == 1)
+                        .map(|(task_ref, _)| task_ref)
+                        .collect();
+                    (
+                        carried.filter_completed_tasks().set_title(&title),
+                        top_level,
+                    )
+                }
+            };
+            if config.carry_over == CarryOver::Migrate {
+                migrate_from = Some((last_note_path, last_note_file, carried_tasks));
+            }
+            note
+        }
+        None => load_template(template.unwrap_or(&config.template), &context)?,
+    };
+    add_recurring_tasks(config, &mut note, date)?;
+    let scheduled_queue = take_scheduled_tasks(config, &mut note, date)?;
+
+    Ok(NewDailyNote {
+        note,
+        scheduled_queue,
+        migrate_from,
+    })
+}
+
+/// Opens the most recent daily note from before `date`
+pub fn open_last_daily_note(config: Config, date: NaiveDate) -> Result<String, String> {
+    let (_, last_note_path) = NoteStore::from_home()
+        .latest_before(date)?
+        .ok_or("No daily notes found")?;
 
     let mut editor_cmd = Command::new(&config.editor_command);
     editor_cmd.arg(&last_note_path);
@@ -94,102 +242,106 @@ pub fn open_last_daily_note(config: Config) -> Result<String, String> {
     ))
 }
 
-fn get_last_daily_note_path() -> Result<PathBuf, String> {
-    let today_note_path = get_daily_note_path();
-    let home = std::env::var("HOME").map_err(|_| "Failed to find HOME env variable")?;
-    let daily_notes_dir = PathBuf::from(&home).join(".worklog").join("daily_notes");
-
-    if !daily_notes_dir.exists() {
-        return Err("Daily notes directory does not exist".to_string());
-    }
-
-    let entries = fs::read_dir(&daily_notes_dir)
-        .map_err(|e| format!("Failed to read daily notes directory: {}", e))?;
-
-    let mut valid_dates = Vec::new();
+/// Daily notes that exist for each day from `from` through `to`, oldest first
+pub fn get_daily_note_paths_between(
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<(NaiveDate, PathBuf)>, String> {
+    NoteStore::from_home().notes_between(from, to)
+}
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
+pub fn get_daily_note_path(date: NaiveDate) -> PathBuf {
+    NoteStore::from_home().path_for(date)
+}
 
-        if !path.is_file() {
+/// Adds the `recurring` tasks due on `date` that the note doesn't already have, e.g.
+/// because yesterday's copy wasn't finished and was carried over
+fn add_recurring_tasks(
+    config: &Config,
+    note: &mut MarkdownFile,
+    date: NaiveDate,
+) -> Result<(), String> {
+    for recurring in &config.recurring {
+        let schedule: Schedule = recurring
+            .every
+            .parse()
+            .map_err(|e| format!("Invalid recurring task '{}': {}", recurring.task, e))?;
+        if !schedule.matches(date) {
             continue;
         }
 
-        let date_str = path.file_stem().and_then(|stem| stem.to_str());
-        if date_str.is_none() {
-            continue;
-        }
-        if today_note_path
-            .to_str()
-            .unwrap()
-            .find(date_str.unwrap())
-            .is_some()
+        let task = ChecklistItem::new(recurring.task.trim().to_string());
+        if note
+            .tasks()
+            .iter()
+            .any(|(_, existing)| existing.id() == task.id())
         {
             continue;
         }
-
-        let date = NaiveDate::parse_from_str(date_str.unwrap_or_default(), "%Y-%m-%d");
-
-        valid_dates.push((date, path));
-    }
-
-    if valid_dates.is_empty() {
-        return Err("No daily notes found".to_string());
+        let section = recurring
+            .section
+            .as_deref()
+            .unwrap_or(&config.default_task_section);
+        add_section_task(note, task, section);
     }
-
-    valid_dates.sort_by(|a, b| {
-        match (&a.0, &b.0) {
-            (Ok(date_a), Ok(date_b)) => date_b.cmp(date_a), // reverse for newest first
-            (Ok(_), Err(_)) => std::cmp::Ordering::Less,
-            (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
-            (Err(_), Err(_)) => std::cmp::Ordering::Equal,
-        }
-    });
-    Ok(valid_dates[0].1.clone())
+    Ok(())
 }
 
-pub fn get_daily_note_path() -> PathBuf {
-    let today_date = get_today_date();
-    get_note_path(&today_date)
+/// The template of the first `template_rules` entry that applies to `date`
+fn template_for_date(config: &Config, date: NaiveDate) -> Result<Option<&str>, String> {
+    for rule in &config.template_rules {
+        let schedule: Schedule = rule
+            .when
+            .parse()
+            .map_err(|e| format!("Invalid template rule for '{}': {}", rule.template, e))?;
+        if schedule.matches(date) {
+            return Ok(Some(&rule.template));
+        }
+    }
+    Ok(None)
 }
 
-fn get_note_path(date: &str) -> PathBuf {
+/// Path of the template named `name` in `~/.worklog/templates`
+pub fn get_template_path(name: &str) -> PathBuf {
     let home = std::env::var("HOME").expect("Failed to find HOME env variable");
-
-    return PathBuf::from(&home)
+    let name = name.strip_suffix(".md").unwrap_or(name);
+    PathBuf::from(&home)
         .join(".worklog")
-        .join("daily_notes")
-        .join(format!("{}.md", date));
+        .join("templates")
+        .join(format!("{}.md", name))
 }
 
-fn from_template_file() -> Result<MarkdownFile, String> {
-    // if the template file does not exist, we should create it with the default template
-    if !PathBuf::from("templates/daily.md").exists() {
-        fs::write(
-            "templates/daily.md",
-            "# {{DATE}}
-
-## Tasks
-
-### Priority
-
-### Support
-
-### Project Management
-
-### Engineering
-
-### Intake
-
-## Notes
-
-    ",
-        )
-        .map_err(|e| format!("Failed to write template: {}", e))?;
+/// Writes out the built-in layout as the default template, so there's one to edit
+fn write_default_template_if_missing() -> Result<(), String> {
+    let template_path = get_template_path(DEFAULT_TEMPLATE_NAME);
+    if template_path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = template_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
     }
+    fs::write(&template_path, DEFAULT_TEMPLATE)
+        .map_err(|e| format!("Failed to write template: {}", e))
+}
 
-    let template = fs::read_to_string("templates/daily.md")
-        .map_err(|e| format!("Failed to read template: {}", e))?;
-    Ok(MarkdownFile::from_string(&template))
+/// Reads and fills in the template named `name`, falling back to the built-in layout for
+/// the default template until it has been written out
+fn load_template(name: &str, context: &TemplateContext) -> Result<MarkdownFile, String> {
+    let template_path = get_template_path(name);
+    let template = if template_path.exists() {
+        fs::read_to_string(&template_path)
+            .map_err(|e| format!("Failed to read {}: {}", template_path.display(), e))?
+    } else if name == DEFAULT_TEMPLATE_NAME {
+        DEFAULT_TEMPLATE.to_string()
+    } else {
+        return Err(format!(
+            "Template '{}' not found, expected it at {}",
+            name,
+            template_path.display()
+        ));
+    };
+    let rendered =
+        render_template(&template, context).map_err(|e| format!("{} in template '{}'", e, name))?;
+    Ok(MarkdownFile::from_string(&rendered))
 }
@@ -0,0 +1,50 @@
+// Tasks can be checked off (or reopened) without opening the editor.
+
+use std::fs;
+
+use chrono::NaiveDate;
+
+use crate::{
+    commands::{
+        daily_note::{create_daily_note_if_not_exists, get_daily_note_path},
+        task::select_task,
+    },
+    config::Config,
+    utils::markdown::checklist::TaskState,
+};
+
+/// Marks the task matching `query` as done, or back to todo when `done` is false
+pub fn set_task_done(
+    config: &Config,
+    query: &str,
+    done: bool,
+    date: NaiveDate,
+    create_fresh: bool,
+) -> Result<String, String> {
+    let daily_note_path = get_daily_note_path(date);
+    let mut daily_note = create_daily_note_if_not_exists(config, date, create_fresh)?;
+
+    // Prefer tasks that would actually change, so `wl done review` skips reviews already done
+    let task_ref = select_task(&daily_note, query, |item| {
+        (item.state == TaskState::Done) != done
+    })?;
+    let task = daily_note
+        .task_mut(&task_ref)
+        .ok_or("Task disappeared while updating it")?;
+
+    let (new_state, verb) = if done {
+        (TaskState::Done, "Completed")
+    } else {
+        (TaskState::Todo, "Reopened")
+    };
+    if task.state == new_state {
+        return Ok(format!("Already {}: {}", verb.to_lowercase(), task.content));
+    }
+    task.state = new_state;
+    let message = format!("{}: {}", verb, task.content);
+
+    fs::write(&daily_note_path, daily_note.to_string())
+        .map_err(|e| format!("Failed to save daily note: {}", e))?;
+
+    Ok(message)
+}
@@ -0,0 +1,74 @@
+// Moves unfinished tasks from one daily note into another, leaving the originals marked
+// as migrated (`[>]`) so each task is only open in one note.
+
+use chrono::NaiveDate;
+
+use crate::{
+    commands::daily_note::{create_daily_note_if_not_exists, get_daily_note_path},
+    config::Config,
+    note_store::NoteStore,
+    utils::markdown::{MarkdownFile, TaskRef},
+};
+
+/// Moves the open tasks in the note dated `from` into the note dated `date`, under the
+/// headings of the same name. Tasks already open under that heading in the target note
+/// aren't added twice, but are still marked migrated in `from`.
+pub fn migrate_tasks(
+    config: &Config,
+    from: NaiveDate,
+    date: NaiveDate,
+    create_fresh: bool,
+) -> Result<String, String> {
+    if from == date {
+        return Err(format!("Can't migrate the {} note into itself", from));
+    }
+
+    let from_path = get_daily_note_path(from);
+    if !from_path.exists() {
+        return Err(format!("No daily note for {}", from));
+    }
+    let daily_note_path = get_daily_note_path(date);
+    // A new target would otherwise start as a copy of `from`'s open tasks
+    let carries_from_source = NoteStore::from_home()
+        .latest_before(date)?
+        .is_some_and(|(previous, _)| previous == from);
+    let mut daily_note =
+        create_daily_note_if_not_exists(config, date, create_fresh || carries_from_source)?;
+    // Read after creating the target, which may already have migrated its tasks
+    let mut from_note = MarkdownFile::from_path(&from_path)
+        .map_err(|e| format!("Failed to read {}: {}", from_path.display(), e))?;
+
+    // Same layout as `from_note`, so the tasks merged from it can be found there again
+    let mut carried = from_note.clone();
+    carried.mark_tasks_since(from);
+    let merged = daily_note.merge_tasks(&carried);
+
+    let moved: Vec<TaskRef> = merged
+        .tasks
+        .into_iter()
+        .chain(merged.already_open)
+        .collect();
+    let migrated = from_note.migrate_tasks(&moved);
+    if migrated == 0 {
+        return Ok(format!("No open tasks to migrate from {}", from));
+    }
+
+    daily_note
+        .save(&daily_note_path)
+        .map_err(|e| format!("Failed to save daily note: {}", e))?;
+    from_note
+        .save(&from_path)
+        .map_err(|e| format!("Failed to save {}: {}", from_path.display(), e))?;
+
+    let mut message = format!(
+        "Migrated {} task{} from {} to {}",
+        migrated,
+        if migrated == 1 { "" } else { "s" },
+        from,
+        date
+    );
+    for heading in merged.headings {
+        message.push_str(&format!("\nAdded missing section: {}", heading));
+    }
+    Ok(message)
+}
@@ -1,3 +1,12 @@
 pub mod daily_note;
+pub mod done;
+pub mod migrate;
 pub mod note;
+pub mod scheduled;
+pub mod standup;
+pub mod summary;
 pub mod task;
+pub mod tasks;
+pub mod timer;
+pub mod timesheet;
+pub mod tui;
@@ -0,0 +1,112 @@
+// Tasks queued for a later day with `wl task --on`. They wait in `~/.worklog/scheduled.md`,
+// under the heading of the section they're for and marked with the day (`⏳ 2025-02-10`),
+// until the daily note for that day or a later one is created.
+
+use std::{fs, path::PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::{
+    commands::task::add_section_task,
+    config::Config,
+    utils::markdown::{MarkdownFile, checklist::ChecklistItem},
+};
+
+/// Queues `task` to be added under `section` in the daily note for `on`
+pub fn schedule_task(
+    mut task: ChecklistItem,
+    section: &str,
+    on: NaiveDate,
+) -> Result<String, String> {
+    let scheduled_path = get_scheduled_path();
+    let mut queue = if scheduled_path.exists() {
+        MarkdownFile::from_path(&scheduled_path)
+            .map_err(|e| format!("Failed to read {}: {}", scheduled_path.display(), e))?
+    } else {
+        MarkdownFile::from_string("# Scheduled\n")
+    };
+
+    let message = format!("Scheduled for {}: {}", on, task.text());
+    task.set_scheduled(Some(on));
+    let heading_index = queue.find_or_insert_heading(section, None);
+    queue.append_task(heading_index, task);
+
+    if let Some(parent_dir) = scheduled_path.parent() {
+        fs::create_dir_all(parent_dir)
+            .map_err(|e| format!("Failed to create {}: {}", parent_dir.display(), e))?;
+    }
+    queue
+        .save(&scheduled_path)
+        .map_err(|e| format!("Failed to save {}: {}", scheduled_path.display(), e))?;
+    Ok(message)
+}
+
+/// Moves the queued tasks for `date` or earlier into `note`, skipping any it already has.
+/// Returns what's left of the queue, to be saved once the note has been, or `None` when
+/// nothing was taken from it.
+pub fn take_scheduled_tasks(
+    config: &Config,
+    note: &mut MarkdownFile,
+    date: NaiveDate,
+) -> Result<Option<MarkdownFile>, String> {
+    let scheduled_path = get_scheduled_path();
+    if !scheduled_path.exists() {
+        return Ok(None);
+    }
+    let mut queue = MarkdownFile::from_path(&scheduled_path)
+        .map_err(|e| format!("Failed to read {}: {}", scheduled_path.display(), e))?;
+
+    let due: Vec<_> = queue
+        .tasks()
+        .into_iter()
+        .filter(|(task_ref, item)| {
+            task_ref.path.len() == 1 && item.scheduled().is_some_and(|on| on <= date)
+        })
+        .map(|(task_ref, _)| {
+            let section = queue
+                .heading_for(task_ref.block_index)
+                .filter(|heading| heading.level > 1)
+                .map(|heading| heading.content.clone());
+            (task_ref, section)
+        })
+        .collect();
+    if due.is_empty() {
+        return Ok(None);
+    }
+
+    // Last first, so removing a task doesn't move the ones still to be removed
+    let mut taken = Vec::new();
+    for (task_ref, section) in due.into_iter().rev() {
+        if let Some(task) = queue.remove_task(&task_ref) {
+            taken.push((task, section));
+        }
+    }
+
+    for (mut task, section) in taken.into_iter().rev() {
+        task.set_scheduled(None);
+        if note
+            .tasks()
+            .iter()
+            .any(|(_, existing)| existing.id() == task.id())
+        {
+            continue;
+        }
+        let section = section.as_deref().unwrap_or(&config.default_task_section);
+        add_section_task(note, task, section);
+    }
+
+    Ok(Some(queue))
+}
+
+/// Writes back the queue returned by `take_scheduled_tasks`
+pub fn save_scheduled_tasks(queue: &MarkdownFile) -> Result<(), String> {
+    let scheduled_path = get_scheduled_path();
+    queue
+        .save(&scheduled_path)
+        .map_err(|e| format!("Failed to save {}: {}", scheduled_path.display(), e))
+}
+
+fn get_scheduled_path() -> PathBuf {
+    let home = std::env::var("HOME").expect("Failed to find HOME env variable");
+    PathBuf::from(&home).join(".worklog").join("scheduled.md")
+}
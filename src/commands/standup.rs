@@ -0,0 +1,141 @@
+// Builds a "yesterday / today / blockers" stand-up update from the daily notes.
+
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::{
+    commands::daily_note::preview_daily_note,
+    config::Config,
+    note_store::NoteStore,
+    utils::markdown::{MarkdownBlock, MarkdownFile, checklist::TaskState},
+};
+
+/// Heading whose items are always reported as blockers
+const BLOCKERS_HEADING: &str = "Blockers";
+
+static MARKDOWN_LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap());
+static MARKDOWN_EMPHASIS_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*\*|__|~~|`").unwrap());
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Standup {
+    /// Tasks completed in the previous daily note
+    pub yesterday: Vec<String>,
+    /// Open tasks under the configured section of today's note
+    pub today: Vec<String>,
+    /// Blocked tasks, and anything under a Blockers heading
+    pub blockers: Vec<String>,
+}
+
+pub fn standup(
+    config: &Config,
+    date: NaiveDate,
+    plain: bool,
+    create_fresh: bool,
+) -> Result<String, String> {
+    let previous_note = match NoteStore::from_home().latest_before(date)? {
+        Some((_, path)) => Some(
+            MarkdownFile::from_path(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+        ),
+        None => None,
+    };
+    // A report shouldn't create today's note, so work out what it would start as
+    let today_note = preview_daily_note(config, date, create_fresh)?;
+
+    let mut standup = build_standup(previous_note.as_ref(), &today_note, &config.standup_section);
+    if plain {
+        for item in standup
+            .yesterday
+            .iter_mut()
+            .chain(&mut standup.today)
+            .chain(&mut standup.blockers)
+        {
+            *item = strip_inline_markdown(item);
+        }
+    }
+
+    Ok(render_standup(&standup, &config.standup_template, date))
+}
+
+pub fn build_standup(
+    previous_note: Option<&MarkdownFile>,
+    today_note: &MarkdownFile,
+    today_section: &str,
+) -> Standup {
+    let yesterday = previous_note
+        .map(|note| {
+            note.tasks()
+                .into_iter()
+                .filter(|(_, item)| item.state == TaskState::Done)
+                .map(|(_, item)| item.text())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let blockers_heading = today_note.find_heading(BLOCKERS_HEADING);
+    let in_section = |block_index: usize, name: &str| {
+        today_note
+            .heading_for(block_index)
+            .is_some_and(|heading| heading.content.eq_ignore_ascii_case(name))
+    };
+
+    let mut today = Vec::new();
+    let mut blockers = Vec::new();
+    for (task_ref, item) in today_note.tasks() {
+        if item.state == TaskState::Blocked
+            || (item.state.is_open() && in_section(task_ref.block_index, BLOCKERS_HEADING))
+        {
+            blockers.push(item.text());
+        } else if item.state.is_open() && in_section(task_ref.block_index, today_section) {
+            today.push(item.text());
+        }
+    }
+
+    // Plain list items under the Blockers heading count too
+    if let Some(heading_index) = blockers_heading {
+        for block in &today_note.blocks[heading_index + 1..today_note.section_end(heading_index)] {
+            if let MarkdownBlock::UnorderedList(list) = block {
+                blockers.extend(list.items.iter().map(|item| item.content.clone()));
+            }
+        }
+    }
+
+    Standup {
+        yesterday,
+        today,
+        blockers,
+    }
+}
+
+fn render_standup(standup: &Standup, template: &str, date: NaiveDate) -> String {
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            "- None".to_string()
+        } else {
+            items
+                .iter()
+                .map(|item| format!("- {}", item))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    };
+
+    template
+        .replace("{{DATE}}", &date.to_string())
+        .replace("{{YESTERDAY}}", &list(&standup.yesterday))
+        .replace("{{TODAY}}", &list(&standup.today))
+        .replace("{{BLOCKERS}}", &list(&standup.blockers))
+        .trim_end()
+        .to_string()
+}
+
+/// Turns `[text](url)` into `text (url)` and drops bold, strikethrough and code markers,
+/// for chat tools that would show them literally
+fn strip_inline_markdown(text: &str) -> String {
+    let text = MARKDOWN_LINK_REGEX.replace_all(text, "$1 ($2)");
+    MARKDOWN_EMPHASIS_REGEX.replace_all(&text, "").to_string()
+}
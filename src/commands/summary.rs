@@ -0,0 +1,310 @@
+// Summarizes the daily notes in a date range: what got done, what is still open and
+// for how long, the notes taken each day and how many tasks each section saw.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    commands::daily_note::get_daily_note_paths_between,
+    utils::markdown::{
+        MarkdownBlock, MarkdownFile,
+        checklist::TaskState,
+        heading::Heading,
+        table::{Alignment, Table},
+        unordered_list::{UnorderedList, UnorderedListItem},
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub completed: Vec<CompletedTask>,
+    pub open: Vec<OpenTask>,
+    pub sections: Vec<SectionCount>,
+    pub notes: Vec<DayNotes>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompletedTask {
+    pub id: String,
+    pub content: String,
+    pub section: Option<String>,
+    /// First daily note the task was checked off in
+    pub completed_on: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenTask {
+    pub id: String,
+    pub content: String,
+    pub section: Option<String>,
+    pub state: TaskState,
+    /// Day the task was first written down, or the first daily note in the range it
+    /// appeared in if it has no `since` date
+    pub first_seen: NaiveDate,
+    /// Days between the task first appearing and the latest note
+    pub days_carried: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SectionCount {
+    pub section: String,
+    pub completed: usize,
+    pub open: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayNotes {
+    pub date: NaiveDate,
+    pub notes: Vec<String>,
+}
+
+pub fn summarize(
+    from: NaiveDate,
+    to: NaiveDate,
+    notes_heading: &str,
+    json: bool,
+) -> Result<String, String> {
+    let summary = build_summary(from, to, notes_heading)?;
+
+    if json {
+        return serde_json::to_string_pretty(&summary)
+            .map_err(|e| format!("Failed to serialize summary: {}", e));
+    }
+    Ok(render_markdown(&summary).to_string().trim_end().to_string())
+}
+
+pub fn build_summary(
+    from: NaiveDate,
+    to: NaiveDate,
+    notes_heading: &str,
+) -> Result<Summary, String> {
+    let mut completed: Vec<CompletedTask> = Vec::new();
+    // Every task seen so far with the day it first appeared, keyed by ID
+    let mut first_seen: Vec<(String, NaiveDate)> = Vec::new();
+    let mut open: Vec<OpenTask> = Vec::new();
+    let mut notes = Vec::new();
+
+    for (date, path) in get_daily_note_paths_between(from, to)? {
+        let note = MarkdownFile::from_path(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        // Only the latest note decides which tasks are still open
+        open.clear();
+        for (task_ref, item, id) in note.tasks_with_ids() {
+            let section = note
+                .heading_for(task_ref.block_index)
+                .map(|heading| heading.content.clone());
+            let seen = match first_seen.iter().find(|(seen_id, _)| *seen_id == id) {
+                Some((_, seen)) => *seen,
+                None => {
+                    let seen = item.origin(date);
+                    first_seen.push((id.clone(), seen));
+                    seen
+                }
+            };
+
+            if item.state == TaskState::Done {
+                // Completed tasks can linger in later notes, count them once
+                if !completed.iter().any(|task| task.id == id) {
+                    completed.push(CompletedTask {
+                        id,
+                        content: item.text(),
+                        section,
+                        completed_on: date,
+                    });
+                }
+            } else if item.state.is_open() {
+                open.push(OpenTask {
+                    id,
+                    content: item.text(),
+                    section,
+                    state: item.state,
+                    first_seen: seen,
+                    days_carried: (date - seen).num_days(),
+                });
+            }
+        }
+
+        let day_notes = notes_in(&note, notes_heading);
+        if !day_notes.is_empty() {
+            notes.push(DayNotes {
+                date,
+                notes: day_notes,
+            });
+        }
+    }
+
+    // A task finished on one day and reopened later is open, not done
+    completed.retain(|task| !open.iter().any(|open| open.id == task.id));
+
+    let mut sections: Vec<SectionCount> = Vec::new();
+    let section_names = completed
+        .iter()
+        .map(|task| (&task.section, true))
+        .chain(open.iter().map(|task| (&task.section, false)));
+    for (section, is_completed) in section_names {
+        let name = section.as_deref().unwrap_or("(no section)");
+        let index = match sections.iter().position(|count| count.section == name) {
+            Some(index) => index,
+            None => {
+                sections.push(SectionCount {
+                    section: name.to_string(),
+                    completed: 0,
+                    open: 0,
+                });
+                sections.len() - 1
+            }
+        };
+        if is_completed {
+            sections[index].completed += 1;
+        } else {
+            sections[index].open += 1;
+        }
+    }
+
+    Ok(Summary {
+        from,
+        to,
+        completed,
+        open,
+        sections,
+        notes,
+    })
+}
+
+/// Top level items of the list(s) directly under the notes heading, with nested lines
+/// indented beneath them
+fn notes_in(note: &MarkdownFile, notes_heading: &str) -> Vec<String> {
+    let Some(heading_index) = note.find_heading(notes_heading) else {
+        return Vec::new();
+    };
+
+    note.blocks[heading_index + 1..note.section_end(heading_index)]
+        .iter()
+        .filter_map(|block| match block {
+            MarkdownBlock::UnorderedList(list) => Some(list),
+            _ => None,
+        })
+        .flat_map(|list| &list.items)
+        .fold(Vec::new(), |mut notes: Vec<String>, item| {
+            match notes.last_mut() {
+                Some(last) if item.indentation_level > 0 => {
+                    last.push('\n');
+                    last.push_str(&item.content);
+                }
+                _ => notes.push(item.content.clone()),
+            }
+            notes
+        })
+}
+
+fn render_markdown(summary: &Summary) -> MarkdownFile {
+    let heading = |level, text: String| MarkdownBlock::Heading(Heading::new(level, text));
+    let list = |items: Vec<String>| {
+        let mut list = UnorderedList::new();
+        for item in items {
+            let mut lines = item.lines();
+            list.items.extend(
+                lines
+                    .next()
+                    .map(|first| UnorderedListItem::new(first, None)),
+            );
+            list.items.extend(lines.map(|line| UnorderedListItem {
+                content: line.to_string(),
+                indentation_level: 1,
+            }));
+        }
+        MarkdownBlock::UnorderedList(list)
+    };
+    let in_section = |section: &Option<String>| {
+        section
+            .as_ref()
+            .map(|section| format!(" ({})", section))
+            .unwrap_or_default()
+    };
+
+    let mut blocks = vec![heading(
+        1,
+        format!("Summary {} to {}", summary.from, summary.to),
+    )];
+
+    blocks.push(heading(
+        2,
+        format!("Completed ({})", summary.completed.len()),
+    ));
+    if !summary.completed.is_empty() {
+        blocks.push(list(
+            summary
+                .completed
+                .iter()
+                .map(|task| {
+                    format!(
+                        "{} — {}{}",
+                        task.content,
+                        task.completed_on,
+                        in_section(&task.section)
+                    )
+                })
+                .collect(),
+        ));
+    }
+
+    blocks.push(heading(2, format!("Still open ({})", summary.open.len())));
+    if !summary.open.is_empty() {
+        blocks.push(list(
+            summary
+                .open
+                .iter()
+                .map(|task| {
+                    let carried = match task.days_carried {
+                        0 => "new".to_string(),
+                        1 => "carried 1 day".to_string(),
+                        days => format!("carried {} days", days),
+                    };
+                    format!(
+                        "[{}] {} — {}{}",
+                        task.state.marker(),
+                        task.content,
+                        carried,
+                        in_section(&task.section)
+                    )
+                })
+                .collect(),
+        ));
+    }
+
+    if !summary.sections.is_empty() {
+        blocks.push(heading(2, "Sections".to_string()));
+        blocks.push(MarkdownBlock::Table(Table::new(
+            vec![
+                "Section".to_string(),
+                "Completed".to_string(),
+                "Open".to_string(),
+            ],
+            vec![Alignment::None, Alignment::Right, Alignment::Right],
+            summary
+                .sections
+                .iter()
+                .map(|count| {
+                    vec![
+                        count.section.clone(),
+                        count.completed.to_string(),
+                        count.open.to_string(),
+                    ]
+                })
+                .collect(),
+        )));
+    }
+
+    if !summary.notes.is_empty() {
+        blocks.push(heading(2, "Notes".to_string()));
+        for day in &summary.notes {
+            blocks.push(heading(3, day.date.format("%Y-%m-%d (%A)").to_string()));
+            blocks.push(list(day.notes.clone()));
+        }
+    }
+
+    MarkdownFile::new(blocks)
+}
@@ -1,47 +1,162 @@
 // Tasks should be able to be added to the daily note.
 
-use std::fs;
+use std::{fs, io};
+
+use chrono::NaiveDate;
 
 use crate::{
     commands::daily_note::{create_daily_note_if_not_exists, get_daily_note_path},
-    utils::markdown::{MarkdownBlock, checklist::Checklist},
+    config::Config,
+    utils::{
+        input::split_lines,
+        markdown::{MarkdownFile, TaskRef, checklist::ChecklistItem},
+    },
 };
 
-pub fn add_task(task: &str, create_fresh: bool) {
-    let daily_note_path = get_daily_note_path();
-    let mut daily_note = create_daily_note_if_not_exists(&daily_note_path, create_fresh)
-        .expect("Failed to verify daily note exists");
-
-    // Look for Intake section first, then Tasks section
-    let mut target_heading_index: Option<usize> = None;
-
-    for (index, block) in daily_note.blocks.iter().enumerate() {
-        if let MarkdownBlock::Heading(heading) = block {
-            if heading.content.contains("Intake") {
-                target_heading_index = Some(index);
-                break;
-            } else if heading.content.contains("Tasks") && target_heading_index.is_none() {
-                target_heading_index = Some(index);
-            }
-        }
+/// Heading the task sections are grouped under in the daily note template
+const TASKS_HEADING: &str = "Tasks";
+
+pub fn add_task(
+    config: &Config,
+    task: &str,
+    parent: Option<&str>,
+    section: &str,
+    due: Option<NaiveDate>,
+    date: NaiveDate,
+    create_fresh: bool,
+) -> Result<(), String> {
+    let item = build_task(task, due)?;
+
+    let daily_note_path = get_daily_note_path(date);
+    let mut daily_note = create_daily_note_if_not_exists(config, date, create_fresh)?;
+
+    match parent {
+        Some(parent) => add_subtask(&mut daily_note, item, parent)?,
+        None => add_section_task(&mut daily_note, item, section),
     }
 
-    if let Some(heading_index) = target_heading_index {
-        let checklist_index = heading_index + 1;
-        let checklist_block = daily_note.blocks.get(checklist_index);
-
-        if let Some(MarkdownBlock::Checklist(checklist)) = checklist_block {
-            let mut new_checklist = checklist.clone();
-            new_checklist.items.push((false, task.to_string()));
-            daily_note.blocks[checklist_index] = MarkdownBlock::Checklist(new_checklist);
-        } else {
-            let mut new_checklist = Checklist::new();
-            new_checklist.items.push((false, task.to_string()));
-            daily_note
-                .blocks
-                .insert(checklist_index, MarkdownBlock::Checklist(new_checklist));
-        }
+    fs::write(&daily_note_path, daily_note.to_string())
+        .map_err(|e| format!("Failed to save daily note: {}", e))
+}
+
+/// Turns `task` into a checklist item, with lines after the first as subtasks and `due`
+/// as a `📅` annotation
+pub fn build_task(task: &str, due: Option<NaiveDate>) -> Result<ChecklistItem, String> {
+    let (first_line, other_lines) =
+        split_lines(task).ok_or("Cannot add a task without a <description>")?;
+    let mut item = ChecklistItem::new(first_line.to_string());
+    if let Some(due) = due {
+        item.set_due(due);
     }
+    for line in other_lines {
+        item.add_child(line.to_string());
+    }
+    Ok(item)
+}
+
+fn add_subtask(
+    daily_note: &mut MarkdownFile,
+    mut task: ChecklistItem,
+    parent: &str,
+) -> Result<(), String> {
+    let parent_ref = select_task(daily_note, parent, |item| item.state.is_open())?;
+    let parent_item = daily_note
+        .task_mut(&parent_ref)
+        .ok_or("Parent task disappeared while adding subtask")?;
+    task.set_indentation_level(parent_item.indentation_level + 1);
+    parent_item.children.push(task);
+    Ok(())
+}
+
+/// Adds `task` to the end of the section under the heading named `section`,
+/// creating the heading beneath the Tasks heading if the note doesn't have it yet
+pub fn add_section_task(daily_note: &mut MarkdownFile, task: ChecklistItem, section: &str) {
+    let heading_index = daily_note.find_or_insert_heading(section, Some(TASKS_HEADING));
+    daily_note.append_task(heading_index, task);
+}
+
+/// Finds the task matching `query`, asking the user to pick one when several match.
+///
+/// `query` may be a task ID from `wl tasks` or part of the task's text. IDs win over
+/// text, exact (case-insensitive) matches win over partial ones, and among those,
+/// tasks satisfying `prefer` win over the rest, e.g. open tasks when completing one.
+pub fn select_task(
+    daily_note: &MarkdownFile,
+    query: &str,
+    prefer: impl Fn(&ChecklistItem) -> bool,
+) -> Result<TaskRef, String> {
+    let needle = query.trim().to_lowercase();
+    let tasks = daily_note.tasks_with_ids();
+
+    let by_id: Vec<_> = tasks.iter().filter(|(_, _, id)| *id == needle).collect();
+    let exact: Vec<_> = tasks
+        .iter()
+        .filter(|(_, item, _)| item.text().to_lowercase() == needle)
+        .collect();
+    let matches = if !by_id.is_empty() {
+        by_id
+    } else if exact.is_empty() {
+        tasks
+            .iter()
+            .filter(|(_, item, _)| item.text().to_lowercase().contains(&needle))
+            .collect()
+    } else {
+        exact
+    };
+
+    let preferred: Vec<_> = matches
+        .iter()
+        .copied()
+        .filter(|(_, item, _)| prefer(item))
+        .collect();
+    let candidates = if preferred.is_empty() {
+        matches
+    } else {
+        preferred
+    };
+
+    match candidates.as_slice() {
+        [] => Err(format!("No task matches '{}'", query)),
+        [(task_ref, _, _)] => Ok(task_ref.clone()),
+        _ => pick_task(daily_note, query, &candidates),
+    }
+}
+
+/// Prints the candidates as a numbered list and reads the user's choice from stdin
+fn pick_task(
+    daily_note: &MarkdownFile,
+    query: &str,
+    candidates: &[&(TaskRef, &ChecklistItem, String)],
+) -> Result<TaskRef, String> {
+    eprintln!("'{}' matches more than one task:", query);
+    for (number, (task_ref, item, id)) in candidates.iter().enumerate() {
+        let section = daily_note
+            .heading_for(task_ref.block_index)
+            .map(|heading| format!(" ({})", heading.content))
+            .unwrap_or_default();
+        eprintln!(
+            "  {}. [{}] {} {}{}",
+            number + 1,
+            item.state.marker(),
+            id,
+            item.content,
+            section
+        );
+    }
+    eprint!("Select a task [1-{}]: ", candidates.len());
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .map_err(|e| format!("Failed to read selection: {}", e))?;
+    eprintln!();
 
-    fs::write(&daily_note_path, daily_note.to_string()).expect("Failed to save daily note");
+    answer
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_sub(1))
+        .and_then(|index| candidates.get(index))
+        .map(|(task_ref, _, _)| task_ref.clone())
+        .ok_or_else(|| format!("No task selected for '{}'", query))
 }
@@ -0,0 +1,192 @@
+// Lists the tasks in one or more daily notes, grouped by the heading they sit under.
+
+use std::io::IsTerminal;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    commands::daily_note::get_daily_note_paths_between,
+    utils::markdown::{MarkdownFile, checklist::TaskState},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    /// Color when writing to a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+pub struct ListOptions {
+    /// First day to include; only the requested date is listed when unset
+    pub since: Option<NaiveDate>,
+    pub section: Option<String>,
+    pub open_only: bool,
+    pub done_only: bool,
+    /// Only list open tasks first written down on or before this day
+    pub stale_before: Option<NaiveDate>,
+    /// Only list open tasks whose due date has passed
+    pub overdue: bool,
+    pub json: bool,
+    pub color: ColorMode,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskEntry {
+    pub date: String,
+    pub section: Option<String>,
+    pub id: String,
+    pub state: TaskState,
+    pub content: String,
+    /// Day the task was first written down, earlier than `date` if it was carried over
+    pub since: String,
+    /// Days since the task was first written down, as of `date`
+    pub age_days: i64,
+    /// The `📅` due date, if the task has one
+    pub due: Option<String>,
+    /// Days past the due date, as of `date`, for open tasks that are overdue
+    pub overdue_days: Option<i64>,
+    /// 0 for top level tasks, 1 for their subtasks and so on
+    pub depth: usize,
+}
+
+pub fn list_tasks(date: NaiveDate, options: &ListOptions) -> Result<String, String> {
+    let entries = collect_tasks(date, options)?;
+
+    if options.json {
+        return serde_json::to_string_pretty(&entries)
+            .map_err(|e| format!("Failed to serialize tasks: {}", e));
+    }
+
+    if entries.is_empty() {
+        return Ok("No tasks found".to_string());
+    }
+
+    let color = match options.color {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => std::io::stdout().is_terminal(),
+    };
+    Ok(render_tasks(&entries, options, color))
+}
+
+pub fn collect_tasks(date: NaiveDate, options: &ListOptions) -> Result<Vec<TaskEntry>, String> {
+    let from = options.since.unwrap_or(date);
+    let mut entries = Vec::new();
+
+    for (note_date, path) in get_daily_note_paths_between(from, date)? {
+        let note = MarkdownFile::from_path(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        for (task_ref, item, id) in note.tasks_with_ids() {
+            let section = note
+                .heading_for(task_ref.block_index)
+                .map(|heading| heading.content.clone());
+
+            if let Some(wanted) = &options.section
+                && !section
+                    .as_deref()
+                    .is_some_and(|section| section.eq_ignore_ascii_case(wanted))
+            {
+                continue;
+            }
+            if options.open_only && !item.state.is_open() {
+                continue;
+            }
+            if options.done_only && item.state != TaskState::Done {
+                continue;
+            }
+            let since = item.origin(note_date);
+            if let Some(stale_before) = options.stale_before
+                && (!item.state.is_open() || since > stale_before)
+            {
+                continue;
+            }
+            let overdue_days = item
+                .due()
+                .filter(|due| item.state.is_open() && *due < note_date)
+                .map(|due| (note_date - due).num_days());
+            if options.overdue && overdue_days.is_none() {
+                continue;
+            }
+
+            entries.push(TaskEntry {
+                date: note_date.to_string(),
+                section,
+                id,
+                state: item.state,
+                content: item.content.clone(),
+                since: since.to_string(),
+                age_days: (note_date - since).num_days(),
+                due: item.due().map(|due| due.to_string()),
+                overdue_days,
+                depth: task_ref.path.len() - 1,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Lists tasks under date and section headings, with how long each has been around
+/// when listing stale tasks and how late each is when listing overdue ones
+fn render_tasks(entries: &[TaskEntry], options: &ListOptions, color: bool) -> String {
+    let mut lines = Vec::new();
+    let mut current_date: Option<&str> = None;
+    let mut current_section: Option<&Option<String>> = None;
+
+    for entry in entries {
+        if current_date != Some(entry.date.as_str()) {
+            if current_date.is_some() {
+                lines.push(String::new());
+            }
+            lines.push(paint(&entry.date, "1;4", color));
+            current_date = Some(&entry.date);
+            current_section = None;
+        }
+        if current_section != Some(&entry.section) {
+            let section = entry.section.as_deref().unwrap_or("(no section)");
+            lines.push(paint(section, "1", color));
+            current_section = Some(&entry.section);
+        }
+
+        let state_color = match entry.state {
+            TaskState::Todo => "0",
+            TaskState::InProgress => "33",
+            TaskState::Blocked => "31",
+            TaskState::Done => "32",
+            TaskState::Cancelled | TaskState::Migrated => "90",
+        };
+        let mut line = format!(
+            "{}{} {} {}",
+            "  ".repeat(entry.depth + 1),
+            paint(&format!("[{}]", entry.state.marker()), state_color, color),
+            paint(&entry.id, "2", color),
+            entry.content
+        );
+        if options.stale_before.is_some() {
+            let age = format!("({}d, since {})", entry.age_days, entry.since);
+            line.push_str(&format!(" {}", paint(&age, "31", color)));
+        }
+        if options.overdue
+            && let Some(days) = entry.overdue_days
+        {
+            let late = format!("({}d overdue)", days);
+            line.push_str(&format!(" {}", paint(&late, "31", color)));
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+/// Wraps `text` in an ANSI SGR escape sequence when color is enabled
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,206 @@
+// A single running timer attached to a task. The timer itself lives in
+// `~/.worklog/timer.yaml` so it survives between invocations (and midnight);
+// when it stops, the elapsed time is added to the task as a `⏱ 1h20m` annotation.
+
+use std::{fs, path::PathBuf};
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::{
+        daily_note::{create_daily_note_if_not_exists, get_daily_note_path},
+        task::select_task,
+    },
+    config::Config,
+    utils::{
+        markdown::{MarkdownFile, checklist::TaskState},
+        time::format_duration,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Timer {
+    pub task_id: String,
+    /// Task text when the timer started, used when the ID no longer matches
+    pub task: String,
+    /// Daily note the task is in; time is recorded there even if the timer runs past midnight
+    pub date: NaiveDate,
+    pub started_at: NaiveDateTime,
+}
+
+impl Timer {
+    pub fn elapsed(&self, now: NaiveDateTime) -> Duration {
+        now - self.started_at
+    }
+}
+
+/// Starts timing the task matching `query`, stopping any timer that is already running
+pub fn start_timer(
+    config: &Config,
+    query: &str,
+    date: NaiveDate,
+    create_fresh: bool,
+) -> Result<String, String> {
+    let daily_note_path = get_daily_note_path(date);
+    let mut daily_note = create_daily_note_if_not_exists(config, date, create_fresh)?;
+    let task_ref = select_task(&daily_note, query, |item| item.state.is_open())?;
+    let task_id = daily_note
+        .tasks_with_ids()
+        .into_iter()
+        .find(|(other, _, _)| *other == task_ref)
+        .map(|(_, _, id)| id)
+        .ok_or("Task disappeared while starting timer")?;
+
+    // Only once the new task is found, so a typo doesn't lose the running timer
+    let mut messages = Vec::new();
+    if load_timer()?.is_some() {
+        messages.push(stop_timer(false)?);
+        // The stopped task may be in this note too, so keep the time just recorded
+        daily_note = MarkdownFile::from_path(&daily_note_path)
+            .map_err(|e| format!("Failed to read {}: {}", daily_note_path.display(), e))?;
+    }
+
+    let task = daily_note
+        .task_mut(&task_ref)
+        .ok_or("Task disappeared while starting timer")?;
+
+    let timer = Timer {
+        task_id,
+        task: task.text(),
+        date,
+        started_at: now(),
+    };
+    messages.push(format!("Started: {}", timer.task));
+
+    if task.state == TaskState::Todo {
+        task.state = TaskState::InProgress;
+        daily_note
+            .save(&daily_note_path)
+            .map_err(|e| format!("Failed to save daily note: {}", e))?;
+    }
+    save_timer(&timer)?;
+
+    Ok(messages.join("\n"))
+}
+
+/// Stops the running timer and adds the elapsed time to its task, or throws it away
+/// when `discard` is set
+pub fn stop_timer(discard: bool) -> Result<String, String> {
+    let timer = load_timer()?.ok_or("No timer is running")?;
+    let elapsed = timer.elapsed(now());
+
+    if discard {
+        clear_timer()?;
+        return Ok(format!(
+            "Discarded {} on: {}",
+            format_duration(elapsed),
+            timer.task
+        ));
+    }
+
+    // Anything under a minute still counts as a minute, so short timers aren't lost
+    let recorded = Duration::minutes(((elapsed.num_seconds() + 30) / 60).max(1));
+
+    let daily_note_path = get_daily_note_path(timer.date);
+    let mut daily_note = MarkdownFile::from_path(&daily_note_path)
+        .map_err(|e| format!("Failed to read {}: {}", daily_note_path.display(), e))?;
+    let task_ref = daily_note
+        .tasks_with_ids()
+        .into_iter()
+        .find(|(_, _, id)| *id == timer.task_id)
+        .or_else(|| {
+            daily_note
+                .tasks_with_ids()
+                .into_iter()
+                .find(|(_, item, _)| item.text().eq_ignore_ascii_case(&timer.task))
+        })
+        .map(|(task_ref, _, _)| task_ref)
+        .ok_or_else(|| {
+            format!(
+                "'{}' is no longer in the {} note, so its time can't be recorded; \
+                 restore the task or run `wl stop --discard`",
+                timer.task, timer.date
+            )
+        })?;
+
+    let task = daily_note
+        .task_mut(&task_ref)
+        .ok_or("Task disappeared while stopping timer")?;
+    task.add_tracked_time(recorded);
+    let total = task.tracked_time().unwrap_or(recorded);
+
+    daily_note
+        .save(&daily_note_path)
+        .map_err(|e| format!("Failed to save daily note: {}", e))?;
+    clear_timer()?;
+
+    Ok(format!(
+        "Stopped: {} ({}, {} total)",
+        timer.task,
+        format_duration(recorded),
+        format_duration(total)
+    ))
+}
+
+/// Describes the running timer, if any
+pub fn timer_status() -> Result<String, String> {
+    let Some(timer) = load_timer()? else {
+        return Ok("No timer running".to_string());
+    };
+
+    let now = now();
+    let since = if timer.started_at.date() == now.date() {
+        timer.started_at.format("%H:%M").to_string()
+    } else {
+        timer.started_at.format("%Y-%m-%d %H:%M").to_string()
+    };
+    Ok(format!(
+        "Tracking: {} [{}] for {} (since {})",
+        timer.task,
+        timer.task_id,
+        format_duration(timer.elapsed(now)),
+        since
+    ))
+}
+
+pub fn load_timer() -> Result<Option<Timer>, String> {
+    let timer_path = get_timer_path();
+    if !timer_path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&timer_path)
+        .map_err(|e| format!("Failed to read {}: {}", timer_path.display(), e))?;
+    serde_yaml::from_str(&contents)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {}", timer_path.display(), e))
+}
+
+fn save_timer(timer: &Timer) -> Result<(), String> {
+    let timer_path = get_timer_path();
+    if let Some(parent_dir) = timer_path.parent() {
+        fs::create_dir_all(parent_dir)
+            .map_err(|e| format!("Failed to create {}: {}", parent_dir.display(), e))?;
+    }
+
+    let contents =
+        serde_yaml::to_string(timer).map_err(|e| format!("Failed to serialize timer: {}", e))?;
+    fs::write(&timer_path, contents)
+        .map_err(|e| format!("Failed to save {}: {}", timer_path.display(), e))
+}
+
+fn clear_timer() -> Result<(), String> {
+    let timer_path = get_timer_path();
+    fs::remove_file(&timer_path)
+        .map_err(|e| format!("Failed to remove {}: {}", timer_path.display(), e))
+}
+
+fn get_timer_path() -> PathBuf {
+    let home = std::env::var("HOME").expect("Failed to find HOME env variable");
+    PathBuf::from(&home).join(".worklog").join("timer.yaml")
+}
+
+fn now() -> NaiveDateTime {
+    Local::now().naive_local()
+}
@@ -0,0 +1,213 @@
+// Sums the time annotations on tasks (`⏱ 45m`, `(1h30m)`) across daily notes.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    commands::daily_note::get_daily_note_paths_between,
+    utils::{markdown::MarkdownFile, time::format_duration},
+};
+
+/// Time recorded on one task in one daily note
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    pub date: NaiveDate,
+    pub section: Option<String>,
+    pub id: String,
+    pub task: String,
+    pub tags: Vec<String>,
+    pub duration: Duration,
+}
+
+pub fn timesheet(from: NaiveDate, to: NaiveDate, csv: bool) -> Result<String, String> {
+    let entries = collect_time_entries(from, to)?;
+
+    if csv {
+        return Ok(render_csv(&entries));
+    }
+    if entries.is_empty() {
+        return Ok(format!("No time recorded from {} to {}", from, to));
+    }
+    Ok(render_report(from, to, &entries))
+}
+
+pub fn collect_time_entries(from: NaiveDate, to: NaiveDate) -> Result<Vec<TimeEntry>, String> {
+    let mut entries = Vec::new();
+
+    for (date, path) in get_daily_note_paths_between(from, to)? {
+        let note = MarkdownFile::from_path(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        for (task_ref, item, id) in note.tasks_with_ids() {
+            let Some(duration) = item.tracked_time() else {
+                continue;
+            };
+            entries.push(TimeEntry {
+                date,
+                section: note
+                    .heading_for(task_ref.block_index)
+                    .map(|heading| heading.content.clone()),
+                id,
+                task: item.text(),
+                tags: item.tags(),
+                duration,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+fn render_report(from: NaiveDate, to: NaiveDate, entries: &[TimeEntry]) -> String {
+    let mut by_day: BTreeMap<NaiveDate, Duration> = BTreeMap::new();
+    // Sections, tags and tasks are listed in the order they first appear
+    let mut by_section: Vec<(String, Duration)> = Vec::new();
+    let mut by_tag: Vec<(String, Duration)> = Vec::new();
+    // Keyed by ID so a task carried over several days is summed into one row
+    let mut by_task: Vec<(&str, (String, String), Duration)> = Vec::new();
+
+    for entry in entries {
+        let section = entry.section.as_deref().unwrap_or("(no section)");
+        *by_day.entry(entry.date).or_default() += entry.duration;
+        add_time(&mut by_section, section.to_string(), entry.duration);
+        for tag in &entry.tags {
+            add_time(&mut by_tag, format!("#{}", tag), entry.duration);
+        }
+        match by_task.iter_mut().find(|(id, ..)| *id == entry.id) {
+            Some((_, _, total)) => *total += entry.duration,
+            None => by_task.push((
+                &entry.id,
+                (entry.task.clone(), section.to_string()),
+                entry.duration,
+            )),
+        }
+    }
+    let total: Duration = entries.iter().map(|entry| entry.duration).sum();
+
+    let mut tables = vec![format!("Timesheet {} to {}", from, to)];
+    tables.push(render_table(
+        &["Section", "Time"],
+        by_section
+            .into_iter()
+            .map(|(section, duration)| vec![section, format_duration(duration)])
+            .collect(),
+        Some(total),
+    ));
+    // Tasks can have several tags or none, so a total here wouldn't add up
+    if !by_tag.is_empty() {
+        tables.push(render_table(
+            &["Tag", "Time"],
+            by_tag
+                .into_iter()
+                .map(|(tag, duration)| vec![tag, format_duration(duration)])
+                .collect(),
+            None,
+        ));
+    }
+    tables.push(render_table(
+        &["Day", "Time"],
+        by_day
+            .into_iter()
+            .map(|(date, duration)| {
+                vec![
+                    date.format("%Y-%m-%d %a").to_string(),
+                    format_duration(duration),
+                ]
+            })
+            .collect(),
+        Some(total),
+    ));
+    tables.push(render_table(
+        &["Task", "Section", "Time"],
+        by_task
+            .into_iter()
+            .map(|(_, (task, section), duration)| vec![task, section, format_duration(duration)])
+            .collect(),
+        Some(total),
+    ));
+
+    tables.join("\n\n")
+}
+
+fn add_time(totals: &mut Vec<(String, Duration)>, key: String, duration: Duration) {
+    match totals.iter_mut().find(|(existing, _)| *existing == key) {
+        Some((_, total)) => *total += duration,
+        None => totals.push((key, duration)),
+    }
+}
+
+/// Lays out rows in aligned columns under a header, with the time in the last column
+/// right aligned and, when given, a total row at the bottom
+fn render_table(headers: &[&str], rows: Vec<Vec<String>>, total: Option<Duration>) -> String {
+    let total_row = total.map(|total| {
+        let mut total_row = vec![String::new(); headers.len()];
+        total_row[0] = "Total".to_string();
+        total_row[headers.len() - 1] = format_duration(total);
+        total_row
+    });
+
+    let header_row: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    let mut widths = vec![0; headers.len()];
+    for row in std::iter::once(&header_row).chain(&rows).chain(&total_row) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+    let format_row = |cells: &Vec<String>| {
+        let last = cells.len() - 1;
+        cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                if index == last {
+                    format!("{:>width$}", cell, width = widths[index])
+                } else {
+                    format!("{:<width$}", cell, width = widths[index])
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    let mut lines = vec![format_row(&header_row), format_row(&separator)];
+    lines.extend(rows.iter().map(format_row));
+    if let Some(total_row) = &total_row {
+        lines.push(format_row(&separator));
+        lines.push(format_row(total_row));
+    }
+    lines.join("\n")
+}
+
+/// One row per task per day, with minutes for spreadsheets alongside the readable duration
+fn render_csv(entries: &[TimeEntry]) -> String {
+    let mut lines = vec!["date,section,task,tags,minutes,duration".to_string()];
+    for entry in entries {
+        lines.push(
+            [
+                entry.date.to_string(),
+                entry.section.clone().unwrap_or_default(),
+                entry.task.clone(),
+                entry.tags.join(" "),
+                entry.duration.num_minutes().to_string(),
+                format_duration(entry.duration),
+            ]
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>()
+            .join(","),
+        );
+    }
+    lines.join("\n")
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,565 @@
+// Full-screen task manager over a daily note, opened with `wl tasks -i`.
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{
+    commands::daily_note::{create_daily_note_if_not_exists, get_daily_note_path},
+    config::Config,
+    utils::markdown::{
+        MarkdownBlock, MarkdownFile, TaskRef,
+        checklist::{ChecklistItem, TaskState},
+    },
+};
+
+/// Opens the task manager for the note dated `date`, highlighting open tasks first written
+/// down on or before `stale_before`
+pub fn run(
+    config: &Config,
+    date: NaiveDate,
+    create_fresh: bool,
+    stale_before: NaiveDate,
+) -> Result<String, String> {
+    let mut terminal =
+        ratatui::try_init().map_err(|e| format!("Failed to start task manager: {}", e))?;
+    let result = create_daily_note_if_not_exists(config, date, create_fresh).and_then(|note| {
+        let mut app = App::new(note, get_daily_note_path(date)).with_stale_before(stale_before);
+        app.run(&mut terminal).map(|()| app.status)
+    });
+    ratatui::restore();
+    result
+}
+
+/// One line of the task list: either a section heading or a task beneath it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Row {
+    Heading {
+        block_index: usize,
+        level: u32,
+        content: String,
+    },
+    Task {
+        task: TaskRef,
+        state: TaskState,
+        content: String,
+        depth: usize,
+        /// Open and carried over since on or before `App::stale_before`
+        stale: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    Normal,
+    /// Picking the heading to move the selected task under, as an index into `App::headings`
+    Move {
+        target: usize,
+    },
+    /// Editing the selected task's text, or a new task for the heading at `adding_to`
+    Edit {
+        buffer: String,
+        adding_to: Option<usize>,
+    },
+    ConfirmDelete,
+    ConfirmQuit,
+}
+
+pub struct App {
+    pub note: MarkdownFile,
+    pub path: PathBuf,
+    pub rows: Vec<Row>,
+    pub cursor: usize,
+    pub mode: Mode,
+    /// Whether there are changes that haven't been saved yet
+    pub modified: bool,
+    pub should_quit: bool,
+    /// Feedback for the last action, shown in the footer and printed on exit
+    pub status: String,
+    /// Open tasks first written down on or before this day are highlighted
+    pub stale_before: Option<NaiveDate>,
+}
+
+impl App {
+    pub fn new(note: MarkdownFile, path: PathBuf) -> Self {
+        let mut app = App {
+            note,
+            path,
+            rows: Vec::new(),
+            cursor: 0,
+            mode: Mode::Normal,
+            modified: false,
+            should_quit: false,
+            status: String::new(),
+            stale_before: None,
+        };
+        app.refresh_rows();
+        // Start on the first task rather than the note's title
+        app.cursor = app
+            .rows
+            .iter()
+            .position(|row| matches!(row, Row::Task { .. }))
+            .unwrap_or(0);
+        app
+    }
+
+    /// Highlights open tasks that have been carried over since on or before `stale_before`
+    pub fn with_stale_before(mut self, stale_before: NaiveDate) -> Self {
+        self.stale_before = Some(stale_before);
+        self.refresh_rows();
+        self
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
+        while !self.should_quit {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .map_err(|e| format!("Failed to draw task manager: {}", e))?;
+
+            if let Event::Key(key) =
+                event::read().map_err(|e| format!("Failed to read input: {}", e))?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match self.mode.clone() {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Move { target } => self.handle_move_key(key, target),
+            Mode::Edit { buffer, adding_to } => self.handle_edit_key(key, buffer, adding_to),
+            Mode::ConfirmDelete => {
+                if key.code == KeyCode::Char('y') {
+                    self.delete_selected();
+                }
+                self.mode = Mode::Normal;
+            }
+            Mode::ConfirmQuit => match key.code {
+                KeyCode::Char('y') => {
+                    self.save();
+                    if self.modified {
+                        self.mode = Mode::Normal;
+                    } else {
+                        self.should_quit = true;
+                    }
+                }
+                KeyCode::Char('n') => {
+                    self.status = "Discarded unsaved changes".to_string();
+                    self.should_quit = true;
+                }
+                _ => self.mode = Mode::Normal,
+            },
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Up if shift => self.reorder_selected(true),
+            KeyCode::Down if shift => self.reorder_selected(false),
+            KeyCode::Char('K') => self.reorder_selected(true),
+            KeyCode::Char('J') => self.reorder_selected(false),
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor = (self.cursor + 1).min(self.rows.len().saturating_sub(1));
+            }
+            KeyCode::Home | KeyCode::Char('g') => self.cursor = 0,
+            KeyCode::End | KeyCode::Char('G') => self.cursor = self.rows.len().saturating_sub(1),
+            KeyCode::Char(' ') | KeyCode::Char('x') => self.toggle_selected(),
+            KeyCode::Char('m') if self.selected_task().is_some() => {
+                let target = self.current_section().unwrap_or(0);
+                self.mode = Mode::Move { target };
+            }
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(Row::Task { content, .. }) = self.rows.get(self.cursor) {
+                    self.mode = Mode::Edit {
+                        buffer: content.clone(),
+                        adding_to: None,
+                    };
+                }
+            }
+            KeyCode::Char('a') => {
+                if let Some(section) = self.current_section() {
+                    self.mode = Mode::Edit {
+                        buffer: String::new(),
+                        adding_to: Some(self.headings()[section]),
+                    };
+                }
+            }
+            KeyCode::Char('d') if self.selected_task().is_some() => {
+                self.mode = Mode::ConfirmDelete;
+            }
+            KeyCode::Char('s') => self.save(),
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if self.modified {
+                    self.mode = Mode::ConfirmQuit;
+                } else {
+                    self.should_quit = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_move_key(&mut self, key: KeyEvent, target: usize) {
+        let heading_count = self.headings().len();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.mode = Mode::Move {
+                    target: target.saturating_sub(1),
+                };
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.mode = Mode::Move {
+                    target: (target + 1).min(heading_count.saturating_sub(1)),
+                };
+            }
+            KeyCode::Enter => {
+                self.move_selected(target);
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::Normal,
+            _ => {}
+        }
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent, mut buffer: String, adding_to: Option<usize>) {
+        match key.code {
+            KeyCode::Enter => {
+                let text = buffer.trim().to_string();
+                if !text.is_empty() {
+                    match adding_to {
+                        Some(heading_index) => self.add_task(heading_index, text),
+                        None => self.edit_selected(text),
+                    }
+                }
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Backspace => {
+                buffer.pop();
+                self.mode = Mode::Edit { buffer, adding_to };
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                self.mode = Mode::Edit { buffer, adding_to };
+            }
+            _ => {}
+        }
+    }
+
+    fn selected_task(&self) -> Option<TaskRef> {
+        match self.rows.get(self.cursor) {
+            Some(Row::Task { task, .. }) => Some(task.clone()),
+            _ => None,
+        }
+    }
+
+    /// Block indexes of every heading, in document order
+    fn headings(&self) -> Vec<usize> {
+        self.note
+            .blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| matches!(block, MarkdownBlock::Heading(_)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Index into `headings` of the section the cursor is in
+    fn current_section(&self) -> Option<usize> {
+        let block_index = self.rows[..=self.cursor.min(self.rows.len().checked_sub(1)?)]
+            .iter()
+            .rev()
+            .find_map(|row| match row {
+                Row::Heading { block_index, .. } => Some(*block_index),
+                _ => None,
+            })?;
+        self.headings()
+            .iter()
+            .position(|index| *index == block_index)
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(task_ref) = self.selected_task() else {
+            return;
+        };
+        if let Some(task) = self.note.task_mut(&task_ref) {
+            task.toggle();
+            self.changed(Some(task_ref));
+        }
+    }
+
+    fn reorder_selected(&mut self, up: bool) {
+        let Some(task_ref) = self.selected_task() else {
+            return;
+        };
+        let Some(MarkdownBlock::Checklist(checklist)) =
+            self.note.blocks.get_mut(task_ref.block_index)
+        else {
+            return;
+        };
+        if let Some(path) = checklist.move_item(&task_ref.path, up) {
+            self.changed(Some(TaskRef {
+                block_index: task_ref.block_index,
+                path,
+            }));
+        }
+    }
+
+    fn move_selected(&mut self, target: usize) {
+        let Some(task_ref) = self.selected_task() else {
+            return;
+        };
+        let heading_count_before = self.headings().len();
+        let Some(item) = self.note.remove_task(&task_ref) else {
+            return;
+        };
+
+        // Removing an emptied checklist shifts block indexes, so look the heading up again
+        let headings = self.headings();
+        debug_assert_eq!(headings.len(), heading_count_before);
+        let Some(heading_index) = headings.get(target).copied() else {
+            return;
+        };
+
+        let content = item.content.clone();
+        let new_ref = self.note.append_task(heading_index, item);
+        self.changed(Some(new_ref));
+        if let Some(MarkdownBlock::Heading(heading)) = self.note.blocks.get(heading_index) {
+            self.status = format!("Moved \"{}\" to {}", content, heading.content);
+        }
+    }
+
+    fn edit_selected(&mut self, text: String) {
+        let Some(task_ref) = self.selected_task() else {
+            return;
+        };
+        if let Some(task) = self.note.task_mut(&task_ref) {
+            task.content = text;
+            self.changed(Some(task_ref));
+        }
+    }
+
+    fn add_task(&mut self, heading_index: usize, text: String) {
+        let new_ref = self
+            .note
+            .append_task(heading_index, ChecklistItem::new(text));
+        self.changed(Some(new_ref));
+    }
+
+    fn delete_selected(&mut self) {
+        let Some(task_ref) = self.selected_task() else {
+            return;
+        };
+        if let Some(item) = self.note.remove_task(&task_ref) {
+            self.changed(None);
+            self.status = format!("Deleted \"{}\"", item.content);
+        }
+    }
+
+    fn save(&mut self) {
+        match self.note.save(&self.path) {
+            Ok(()) => {
+                self.modified = false;
+                self.status = format!("Saved {}", self.path.display());
+            }
+            Err(error) => self.status = format!("Failed to save daily note: {}", error),
+        }
+    }
+
+    /// Marks the note as modified and rebuilds the rows, keeping the cursor on `select` if given
+    fn changed(&mut self, select: Option<TaskRef>) {
+        self.modified = true;
+        self.status.clear();
+        self.refresh_rows();
+
+        if let Some(select) = select
+            && let Some(position) = self
+                .rows
+                .iter()
+                .position(|row| matches!(row, Row::Task { task, .. } if *task == select))
+        {
+            self.cursor = position;
+        }
+        self.cursor = self.cursor.min(self.rows.len().saturating_sub(1));
+    }
+
+    fn refresh_rows(&mut self) {
+        self.rows.clear();
+        for (block_index, block) in self.note.blocks.iter().enumerate() {
+            match block {
+                MarkdownBlock::Heading(heading) => self.rows.push(Row::Heading {
+                    block_index,
+                    level: heading.level,
+                    content: heading.content.clone(),
+                }),
+                MarkdownBlock::Checklist(checklist) => {
+                    for (path, item) in checklist.iter_items() {
+                        self.rows.push(Row::Task {
+                            depth: path.len() - 1,
+                            task: TaskRef { block_index, path },
+                            state: item.state,
+                            content: item.content.clone(),
+                            stale: item.state.is_open()
+                                && item
+                                    .since
+                                    .zip(self.stale_before)
+                                    .is_some_and(|(since, stale_before)| since <= stale_before),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header_area, list_area, footer_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let title = format!(
+            " {}{}",
+            self.path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default(),
+            if self.modified { " [modified]" } else { "" }
+        );
+        frame.render_widget(
+            Paragraph::new(title).style(Style::new().add_modifier(Modifier::BOLD)),
+            header_area,
+        );
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| ListItem::new(self.row_line(index, row)))
+            .collect();
+        let mut list_state = ListState::default().with_selected(Some(self.cursor));
+        frame.render_stateful_widget(
+            List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            list_area,
+            &mut list_state,
+        );
+
+        frame.render_widget(Paragraph::new(self.footer_text()), footer_area);
+
+        if let Mode::Move { target } = &self.mode {
+            self.draw_move_popup(frame, *target);
+        }
+    }
+
+    fn row_line(&self, index: usize, row: &Row) -> Line<'_> {
+        match row {
+            Row::Heading { level, content, .. } => Line::from(Span::styled(
+                format!("{} {}", "#".repeat(*level as usize), content),
+                Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )),
+            Row::Task {
+                state,
+                content,
+                depth,
+                stale,
+                ..
+            } => {
+                let text = match &self.mode {
+                    Mode::Edit {
+                        buffer,
+                        adding_to: None,
+                    } if index == self.cursor => format!("{}▏", buffer),
+                    _ => content.clone(),
+                };
+                let style = match state {
+                    TaskState::Done => Style::new().fg(Color::Green),
+                    TaskState::InProgress => Style::new().fg(Color::Yellow),
+                    TaskState::Blocked => Style::new().fg(Color::Red),
+                    TaskState::Cancelled | TaskState::Migrated => Style::new()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                    TaskState::Todo => Style::new(),
+                };
+                let text_style = if *stale {
+                    Style::new()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::ITALIC)
+                } else {
+                    Style::new()
+                };
+                Line::from(vec![
+                    Span::raw("  ".repeat(depth + 1)),
+                    Span::styled(format!("[{}] ", state.marker()), style),
+                    Span::styled(text, text_style),
+                ])
+            }
+        }
+    }
+
+    fn footer_text(&self) -> String {
+        match &self.mode {
+            Mode::Normal if !self.status.is_empty() => self.status.clone(),
+            Mode::Normal => "↑↓ move  space toggle  J/K reorder  m move to section  e edit  a add  d delete  s save  q quit".to_string(),
+            Mode::Move { .. } => "↑↓ choose section  enter move  esc cancel".to_string(),
+            Mode::Edit {
+                buffer,
+                adding_to: Some(_),
+            } => format!("New task: {}▏", buffer),
+            Mode::Edit { .. } => "enter save  esc cancel".to_string(),
+            Mode::ConfirmDelete => "Delete this task and its subtasks? (y/n)".to_string(),
+            Mode::ConfirmQuit => "Save changes before quitting? (y/n, esc to cancel)".to_string(),
+        }
+    }
+
+    fn draw_move_popup(&self, frame: &mut Frame, target: usize) {
+        let headings: Vec<ListItem> = self
+            .headings()
+            .iter()
+            .filter_map(|index| match &self.note.blocks[*index] {
+                MarkdownBlock::Heading(heading) => Some(ListItem::new(format!(
+                    "{}{}",
+                    "  ".repeat(heading.level.saturating_sub(1) as usize),
+                    heading.content
+                ))),
+                _ => None,
+            })
+            .collect();
+
+        let area = centered_rect(frame.area(), 40, headings.len() as u16 + 2);
+        let mut list_state = ListState::default().with_selected(Some(target));
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(
+            List::new(headings)
+                .block(Block::default().borders(Borders::ALL).title(" Move to "))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            area,
+            &mut list_state,
+        );
+    }
+}
+
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
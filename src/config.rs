@@ -1,17 +1,95 @@
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 // todo: want to read from `~/.worklog.toml` and parse it into a Config struct
 
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     pub editor_command: String,
+    /// Heading `wl task` adds tasks under when no `--section` is given
+    pub default_task_section: String,
+    /// Flags standing in for `--section <heading>`, e.g. `eng: Engineering` enables `wl task --eng`
+    pub section_shorthands: BTreeMap<String, String>,
+    /// Heading `wl note` adds notes under, created at the end of the note if missing
+    pub notes_heading: String,
+    /// Prefix notes with the time they were added, unless `wl note --no-time` is given
+    pub note_timestamps: bool,
+    /// Heading whose open tasks `wl standup` lists as today's plan
+    pub standup_section: String,
+    /// Layout of `wl standup`, filled in from `{{DATE}}`, `{{YESTERDAY}}`, `{{TODAY}}` and `{{BLOCKERS}}`
+    pub standup_template: String,
+    /// How long a task can be carried over before the task manager highlights it, e.g. `5d`
+    pub stale_after: String,
+    /// Template new notes start from when there's no previous note to carry over from,
+    /// by name from `~/.worklog/templates` (`daily` is `~/.worklog/templates/daily.md`)
+    pub template: String,
+    /// Templates for particular days, e.g. `{ when: monday, template: planning }`; the first
+    /// rule matching a new note's date is used instead of `template`
+    pub template_rules: Vec<TemplateRule>,
+    /// Extra values templates can use, e.g. `team: Platform` for `{{team}}`
+    pub template_variables: BTreeMap<String, String>,
+    /// Start every new note from its template and merge the previous note's open tasks into
+    /// it, rather than copying the previous note, so template changes reach new notes
+    pub merge_into_template: bool,
+    /// Tasks added to new notes on the days they're due, e.g.
+    /// `{ task: "Review dashboards", every: weekday, section: Support }`
+    pub recurring: Vec<RecurringTask>,
+    /// What happens to open tasks in the previous note when a new note carries them over
+    pub carry_over: CarryOver,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TemplateRule {
+    /// Days the rule applies to, e.g. `monday`, `mon,thu`, `first workday of month` or `2025-12-24`
+    pub when: String,
+    pub template: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RecurringTask {
+    pub task: String,
+    /// Days to add the task on, e.g. `weekday`, `mon,thu` or `1st of month`
+    pub every: String,
+    /// Heading to add the task under, `default_task_section` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CarryOver {
+    /// Leave them open in the previous note as well
+    #[default]
+    Copy,
+    /// Mark them migrated (`[>]`) in the previous note, so each task is open in one note only
+    Migrate,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             editor_command: "nvim".to_string(),
+            default_task_section: "Intake".to_string(),
+            section_shorthands: BTreeMap::from([
+                ("priority".to_string(), "Priority".to_string()),
+                ("support".to_string(), "Support".to_string()),
+                ("pm".to_string(), "Project Management".to_string()),
+                ("eng".to_string(), "Engineering".to_string()),
+            ]),
+            notes_heading: "Notes".to_string(),
+            note_timestamps: false,
+            standup_section: "Priority".to_string(),
+            standup_template:
+                "Yesterday:\n{{YESTERDAY}}\n\nToday:\n{{TODAY}}\n\nBlockers:\n{{BLOCKERS}}\n"
+                    .to_string(),
+            stale_after: "5d".to_string(),
+            template: "daily".to_string(),
+            template_rules: Vec::new(),
+            template_variables: BTreeMap::new(),
+            merge_into_template: false,
+            recurring: Vec::new(),
+            carry_over: CarryOver::Copy,
         }
     }
 }
@@ -20,14 +98,11 @@ pub fn load_config() -> Result<Config, String> {
     let config_path = get_config_path();
 
     match fs::read_to_string(&config_path) {
-        Ok(contents) => {
-            let user_config: Config = serde_yaml::from_str(&contents)
-                .expect("Failed to parse Config from `~/.worklog/config.yaml`");
-            return Ok(user_config);
-        }
+        Ok(contents) => serde_yaml::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e)),
         Err(_) => {
             set_config(Config::default());
-            return Ok(Config::default());
+            Ok(Config::default())
         }
     }
 }
@@ -47,5 +122,5 @@ fn set_config(config: Config) {
 
 fn get_config_path() -> PathBuf {
     let home = std::env::var("HOME").expect("Failed to find HOME env variable");
-    return PathBuf::from(&home).join(".worklog").join("config.yaml");
+    PathBuf::from(&home).join(".worklog").join("config.yaml")
 }
@@ -1,8 +1,9 @@
-use clap::{Parser, Subcommand};
+use std::collections::HashSet;
 
-mod commands;
-mod config;
-mod utils;
+use chrono::{NaiveDate, NaiveTime};
+use clap::{CommandFactory, Parser, Subcommand};
+
+use worklog_cli::{commands, config, utils};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -17,53 +18,412 @@ struct Cli {
     // only used for root and open commands, to ensure daily note is made fresh from template
     #[arg(long, default_value_t = false)]
     fresh: bool,
+
+    /// Date of the daily note to use, e.g. 2025-01-15, yesterday, -3d, last friday, next monday
+    #[arg(long, global = true, value_parser = utils::time::parse_date_arg)]
+    date: Option<NaiveDate>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    Open,
+    Open {
+        /// Start a new note from this template in `~/.worklog/templates` instead of the
+        /// configured one, keeping the open tasks from the previous note
+        #[arg(long)]
+        template: Option<String>,
+    },
     Last,
     Task {
-        description: String,
-        // todo: would be cool to be able to do something like `wl task "Draft contract for Will" --project-planning`
-        // #[arg(long)]
-        // category: String,
+        /// Task text, or `-` to read it from stdin; lines after the first become subtasks
+        #[arg(required_unless_present = "edit")]
+        description: Option<String>,
+        /// Write the task in a scratch buffer opened with `editor_command`
+        #[arg(short, long, conflicts_with = "description")]
+        edit: bool,
+        /// Add as a subtask of the task matching this text
+        #[arg(long)]
+        parent: Option<String>,
+        /// Heading to add the task under, created if missing (defaults to `default_task_section`).
+        /// Shorthands such as `--eng` can be set up under `section_shorthands` in the config
+        #[arg(long, conflicts_with = "parent")]
+        section: Option<String>,
+        /// Due date, e.g. friday, +3d or 2025-02-01, added to the task as `📅 2025-02-01`
+        #[arg(long, value_name = "DATE")]
+        due: Option<String>,
+        /// Add the task to the note for this day instead. If that note doesn't exist yet the
+        /// task waits in ~/.worklog/scheduled.md until it's created
+        #[arg(long, value_name = "DATE", conflicts_with = "parent")]
+        on: Option<String>,
     },
     Note {
-        description: String,
+        /// Note text, or `-` to read it from stdin; lines after the first are nested beneath it
+        #[arg(required_unless_present = "edit")]
+        description: Option<String>,
+        /// Write the note in a scratch buffer opened with `editor_command`
+        #[arg(short, long, conflicts_with = "description")]
+        edit: bool,
+        /// Prefix the note with the current time (the default when `note_timestamps` is set)
+        #[arg(long, overrides_with = "no_time")]
+        time: bool,
+        /// Don't prefix the note with the time
+        #[arg(long, overrides_with = "time")]
+        no_time: bool,
+        /// Time to log the note at instead of now, e.g. 09:15 or 2:30pm
+        #[arg(long, conflicts_with = "no_time", value_parser = utils::time::parse_time_arg)]
+        at: Option<NaiveTime>,
+    },
+    /// Check off the task matching the given text
+    Done {
+        task: String,
+    },
+    /// Reopen a completed task matching the given text
+    Undo {
+        task: String,
+    },
+    /// Move open tasks from an earlier note into this one, marking the originals migrated
+    Migrate {
+        /// Note to take the open tasks from, e.g. yesterday or 2025-01-14
+        #[arg(long, value_parser = utils::time::parse_date_arg)]
+        from: NaiveDate,
+    },
+    /// Start timing the task matching the given ID or text, stopping any running timer
+    Start {
+        task: String,
+    },
+    /// Stop the running timer and add the time to its task
+    Stop {
+        /// Throw the running time away instead of recording it
+        #[arg(long)]
+        discard: bool,
+    },
+    /// Show the running timer
+    Status,
+    /// Sum the time recorded on tasks per section, tag, day and task
+    Timesheet {
+        /// Cover the week (Monday to Sunday) containing the date
+        #[arg(long, conflicts_with_all = ["month", "from", "to"])]
+        week: bool,
+        /// Cover the month containing the date
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        month: bool,
+        /// First day to include, e.g. 2025-01-01 or -2w
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include, defaulting to the date
+        #[arg(long)]
+        to: Option<String>,
+        /// Print one row per task and day as CSV
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Print a stand-up update: yesterday's completed tasks, today's plan and blockers
+    Standup {
+        /// Strip Markdown links and formatting from task text, for pasting into chat
+        #[arg(long)]
+        plain: bool,
+    },
+    /// Summarize completed and open tasks, notes and sections over a date range
+    Summary {
+        /// Include every daily note from this far back, e.g. 30d, 2w or 2025-01-01
+        #[arg(long, default_value = "7d")]
+        since: String,
+        /// Print the summary as JSON instead of Markdown
+        #[arg(long)]
+        json: bool,
+    },
+    /// List tasks grouped by section, with IDs usable by other commands
+    Tasks {
+        /// Only list tasks under this heading
+        #[arg(long)]
+        section: Option<String>,
+        /// Only list open tasks (todo, in progress or blocked)
+        #[arg(long, conflicts_with = "done")]
+        open: bool,
+        /// Only list completed tasks
+        #[arg(long)]
+        done: bool,
+        /// Include every daily note from this far back, e.g. 7d, 2w or 2025-01-01
+        #[arg(long)]
+        since: Option<String>,
+        /// Only list open tasks carried over for at least this long, e.g. 5d or 2w
+        #[arg(long, value_name = "AGE", conflicts_with = "done")]
+        stale: Option<String>,
+        /// Only list open tasks whose due date has passed
+        #[arg(long, conflicts_with = "done")]
+        overdue: bool,
+        /// Print tasks as JSON
+        #[arg(long)]
+        json: bool,
+        #[arg(long, value_enum, default_value_t = commands::tasks::ColorMode::Auto)]
+        color: commands::tasks::ColorMode,
+        /// Open a full-screen task manager for the note instead of listing
+        #[arg(short, long)]
+        interactive: bool,
     },
 }
 
 fn main() {
-    let cli = Cli::parse();
-
-    let config = config::load_config().expect("Failed to parse config");
+    // Parse before loading the config so `--help` and `--version` work even when it's broken;
+    // only the section shorthands need it to be read first
+    let (cli, config) = match Cli::try_parse_from(std::env::args()) {
+        Ok(cli) => (cli, load_config()),
+        Err(error) if !error.use_stderr() => error.exit(),
+        Err(_) => {
+            let config = load_config();
+            let args = expand_section_shorthands(std::env::args(), &config);
+            (Cli::parse_from(args), config)
+        }
+    };
+    let date = cli.date.unwrap_or_else(utils::time::get_today_date);
 
     // You can check for the existence of subcommands, and if found use their
     // matches just as you would the top level cmd
     match &cli.command {
-        Some(Commands::Open) | None => {
-            commands::daily_note::open_daily_note(config, cli.fresh)
+        Some(Commands::Open { template }) => {
+            commands::daily_note::open_daily_note(config, date, cli.fresh, template.as_deref())
+                .expect("Failed to open daily note");
+        }
+        None => {
+            commands::daily_note::open_daily_note(config, date, cli.fresh, None)
                 .expect("Failed to open daily note");
         }
         Some(Commands::Last) => {
-            commands::daily_note::open_last_daily_note(config)
+            commands::daily_note::open_last_daily_note(config, date)
                 .expect("Failed to open last daily note");
         }
-        Some(Commands::Task { description }) => {
-            if description.is_empty() {
+        Some(Commands::Task {
+            description,
+            edit,
+            parent,
+            section,
+            due,
+            on,
+        }) => {
+            let resolve = |input: &Option<String>| {
+                input
+                    .as_deref()
+                    .map(|input| utils::time::resolve_future_date(input, date))
+                    .transpose()
+                    .unwrap_or_else(|error| exit_with_error(error))
+            };
+            let due = resolve(due);
+            let on = resolve(on);
+            let section = section.as_deref().unwrap_or(&config.default_task_section);
+            let description = read_description(description.as_deref(), *edit, &config);
+            if description.trim().is_empty() {
                 println!("Cannot add a task without a <description>");
-            } else {
-                commands::task::add_task(description, cli.fresh);
+                return;
+            }
+
+            let result = match on {
+                // Nothing to add it to yet, so queue it for when that day's note is created
+                Some(on)
+                    if on > date && !commands::daily_note::get_daily_note_path(on).exists() =>
+                {
+                    commands::task::build_task(&description, due)
+                        .and_then(|item| commands::scheduled::schedule_task(item, section, on))
+                        .map(|message| println!("{}", message))
+                }
+                _ => commands::task::add_task(
+                    &config,
+                    &description,
+                    parent.as_deref(),
+                    section,
+                    due,
+                    on.unwrap_or(date),
+                    cli.fresh,
+                ),
+            };
+            if let Err(error) = result {
+                exit_with_error(error);
+            }
+        }
+        Some(Commands::Note {
+            description,
+            edit,
+            time,
+            no_time,
+            at,
+        }) => {
+            let timestamped = (config.note_timestamps || *time) && !*no_time;
+            let time = at.or_else(|| timestamped.then(utils::time::get_current_time));
+            let description = read_description(description.as_deref(), *edit, &config);
+            if let Err(error) =
+                commands::note::add_note(&config, &description, time, date, cli.fresh)
+            {
+                exit_with_error(error);
             }
         }
-        Some(Commands::Note { description }) => {
-            if description.is_empty() {
-                println!("Cannot add a note without a <description>");
+        Some(Commands::Done { task }) => {
+            report(commands::done::set_task_done(
+                &config, task, true, date, cli.fresh,
+            ));
+        }
+        Some(Commands::Undo { task }) => {
+            report(commands::done::set_task_done(
+                &config, task, false, date, cli.fresh,
+            ));
+        }
+        Some(Commands::Migrate { from }) => {
+            report(commands::migrate::migrate_tasks(
+                &config, *from, date, cli.fresh,
+            ));
+        }
+        Some(Commands::Start { task }) => {
+            report(commands::timer::start_timer(&config, task, date, cli.fresh));
+        }
+        Some(Commands::Stop { discard }) => report(commands::timer::stop_timer(*discard)),
+        Some(Commands::Status) => report(commands::timer::timer_status()),
+        Some(Commands::Timesheet {
+            week,
+            month,
+            from,
+            to,
+            csv,
+        }) => {
+            let resolve = |input: &Option<String>| {
+                input
+                    .as_deref()
+                    .map(|input| utils::time::resolve_date(input, date))
+                    .transpose()
+                    .unwrap_or_else(|error| exit_with_error(error))
+            };
+            let (from, to) = if *week {
+                utils::time::week_bounds(date)
+            } else if *month {
+                utils::time::month_bounds(date)
+            } else {
+                let to = resolve(to).unwrap_or(date);
+                (resolve(from).unwrap_or(to), to)
+            };
+
+            report(commands::timesheet::timesheet(from, to, *csv));
+        }
+        Some(Commands::Standup { plain }) => {
+            report(commands::standup::standup(&config, date, *plain, cli.fresh));
+        }
+        Some(Commands::Summary { since, json }) => {
+            let from = utils::time::resolve_since(since, date)
+                .unwrap_or_else(|error| exit_with_error(error));
+            report(commands::summary::summarize(
+                from,
+                date,
+                &config.notes_heading,
+                *json,
+            ));
+        }
+        Some(Commands::Tasks {
+            section,
+            open,
+            done,
+            since,
+            stale,
+            overdue,
+            json,
+            color,
+            interactive,
+        }) => {
+            if *interactive {
+                let stale_before = utils::time::resolve_since(&config.stale_after, date)
+                    .unwrap_or_else(|error| {
+                        exit_with_error(format!("Invalid stale_after in config: {}", error))
+                    });
+                report(commands::tui::run(&config, date, cli.fresh, stale_before));
+                return;
             }
-            commands::note::add_note(description, cli.fresh);
+
+            let resolve = |input: &Option<String>| {
+                input
+                    .as_deref()
+                    .map(|input| utils::time::resolve_since(input, date))
+                    .transpose()
+                    .unwrap_or_else(|error| exit_with_error(error))
+            };
+            let since = resolve(since);
+            let stale_before = resolve(stale);
+            let options = commands::tasks::ListOptions {
+                since,
+                section: section.clone(),
+                open_only: *open,
+                done_only: *done,
+                stale_before,
+                overdue: *overdue,
+                json: *json,
+                color: *color,
+            };
+            report(commands::tasks::list_tasks(date, &options));
         }
     }
 
     // Continued program logic goes here...
 }
+
+fn load_config() -> config::Config {
+    config::load_config().unwrap_or_else(|error| exit_with_error(error))
+}
+
+/// Prints a command's output, or its error before exiting with a failure status
+fn report(result: Result<String, String>) {
+    match result {
+        Ok(output) => println!("{}", output),
+        Err(error) => exit_with_error(error),
+    }
+}
+
+fn exit_with_error(error: String) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(1);
+}
+
+/// Reads task or note text from the argument, stdin or an editor buffer, exiting on failure
+fn read_description(description: Option<&str>, edit: bool, config: &config::Config) -> String {
+    utils::input::read_text(description, edit, &config.editor_command)
+        .unwrap_or_else(|error| exit_with_error(error))
+}
+
+/// Rewrites the section shorthands defined in the config, e.g. `--eng` into
+/// `--section Engineering`, for the subcommands that take a section. Shorthands named
+/// like a built-in flag are left alone.
+fn expand_section_shorthands(
+    args: impl Iterator<Item = String>,
+    config: &config::Config,
+) -> Vec<String> {
+    let command = Cli::command();
+    let builtin_flags: HashSet<&str> = command
+        .get_arguments()
+        .chain(
+            command
+                .get_subcommands()
+                .flat_map(|sub| sub.get_arguments()),
+        )
+        .filter_map(|arg| arg.get_long())
+        .collect();
+    let subcommands: HashSet<&str> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name())
+        .collect();
+
+    let mut expanded = Vec::new();
+    let mut subcommand: Option<String> = None;
+    let mut options_ended = false;
+    for arg in args {
+        options_ended |= arg == "--";
+        if subcommand.is_none() && subcommands.contains(arg.as_str()) {
+            subcommand = Some(arg.clone());
+        }
+        let takes_section = matches!(subcommand.as_deref(), Some("task" | "tasks"));
+        let shorthand = arg
+            .strip_prefix("--")
+            .filter(|name| takes_section && !options_ended && !builtin_flags.contains(name))
+            .and_then(|name| config.section_shorthands.get(name));
+
+        match shorthand {
+            Some(section) => {
+                expanded.push("--section".to_string());
+                expanded.push(section.clone());
+            }
+            None => expanded.push(arg),
+        }
+    }
+    expanded
+}
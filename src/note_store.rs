@@ -0,0 +1,92 @@
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use chrono::NaiveDate;
+
+/// The directory of daily notes, one `YYYY-MM-DD.md` file per day. Anything else in the
+/// directory (backups, scratch files, other markdown) is ignored.
+#[derive(Debug, Clone)]
+pub struct NoteStore {
+    dir: PathBuf,
+}
+
+impl NoteStore {
+    pub fn new(dir: PathBuf) -> Self {
+        NoteStore { dir }
+    }
+
+    /// The store at `~/.worklog/daily_notes`
+    pub fn from_home() -> Self {
+        let home = std::env::var("HOME").expect("Failed to find HOME env variable");
+        NoteStore::new(PathBuf::from(&home).join(".worklog").join("daily_notes"))
+    }
+
+    pub fn path_for(&self, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!("{}.md", date.format("%Y-%m-%d")))
+    }
+
+    /// Dates of every note in the store, oldest first
+    pub fn dates(&self) -> Result<Vec<NaiveDate>, String> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(format!(
+                    "Failed to read daily notes directory {}: {}",
+                    self.dir.display(),
+                    e
+                ));
+            }
+        };
+
+        let mut dates = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let Some(date) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(parse_note_file_name)
+            else {
+                continue;
+            };
+            dates.push(date);
+        }
+
+        dates.sort();
+        Ok(dates)
+    }
+
+    /// The most recent note dated strictly before `date`
+    pub fn latest_before(&self, date: NaiveDate) -> Result<Option<(NaiveDate, PathBuf)>, String> {
+        Ok(self
+            .dates()?
+            .into_iter()
+            .rfind(|note_date| *note_date < date)
+            .map(|note_date| (note_date, self.path_for(note_date))))
+    }
+
+    /// Notes that exist for each day from `from` through `to`, oldest first
+    pub fn notes_between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, PathBuf)>, String> {
+        Ok(self
+            .dates()?
+            .into_iter()
+            .filter(|date| (from..=to).contains(date))
+            .map(|date| (date, self.path_for(date)))
+            .collect())
+    }
+}
+
+/// The date of a note's file name, only for names written exactly as `YYYY-MM-DD.md`
+fn parse_note_file_name(name: &str) -> Option<NaiveDate> {
+    let stem = name.strip_suffix(".md")?;
+    let date = NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()?;
+    // chrono accepts unpadded fields like `2025-1-5`, which aren't names we write
+    (date.format("%Y-%m-%d").to_string() == stem).then_some(date)
+}
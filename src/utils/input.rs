@@ -0,0 +1,61 @@
+use std::{
+    fs,
+    io::{self, Read},
+    process::Command,
+};
+
+/// Resolves the text for a task or note: the argument itself, all of stdin when the
+/// argument is `-`, or whatever is saved from a scratch buffer when `edit` is set
+pub fn read_text(
+    argument: Option<&str>,
+    edit: bool,
+    editor_command: &str,
+) -> Result<String, String> {
+    if edit {
+        return read_from_editor(editor_command);
+    }
+
+    match argument {
+        Some("-") => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("Failed to read from stdin: {}", e))?;
+            Ok(text)
+        }
+        Some(argument) => Ok(argument.to_string()),
+        None => Ok(String::new()),
+    }
+}
+
+/// Opens an empty scratch file in the editor and returns what was saved to it
+fn read_from_editor(editor_command: &str) -> Result<String, String> {
+    let scratch_path = std::env::temp_dir().join(format!("wl-{}.md", std::process::id()));
+    fs::write(&scratch_path, "")
+        .map_err(|e| format!("Failed to create {}: {}", scratch_path.display(), e))?;
+
+    let status = Command::new(editor_command)
+        .arg(&scratch_path)
+        .status()
+        .map_err(|e| format!("Failed to run editor '{}': {}", editor_command, e));
+    let text = fs::read_to_string(&scratch_path)
+        .map_err(|e| format!("Failed to read {}: {}", scratch_path.display(), e));
+    let _ = fs::remove_file(&scratch_path);
+
+    let status = status?;
+    if !status.success() {
+        return Err(format!(
+            "Editor command failed with exit code {:?}",
+            status.code()
+        ));
+    }
+    text
+}
+
+/// Splits multi-line text into its first line and the non-empty lines after it,
+/// trimmed, so they can be stored as nested list items
+pub fn split_lines(text: &str) -> Option<(&str, Vec<&str>)> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let first = lines.next()?;
+    Some((first, lines.collect()))
+}
@@ -0,0 +1,60 @@
+use regex::Regex;
+use std::{fmt, sync::LazyLock};
+
+use crate::utils::markdown::MarkdownBlock;
+
+pub static BLOCK_QUOTE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*>\s?(.*?)\s*$").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockQuote {
+    /// Quoted lines with their `>` marker removed
+    pub lines: Vec<String>,
+    pub source: Option<String>,
+}
+
+impl BlockQuote {
+    pub fn new(lines: Vec<String>) -> Self {
+        BlockQuote {
+            lines,
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for BlockQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let content = self
+            .lines
+            .iter()
+            .map(|line| {
+                if line.is_empty() {
+                    ">".to_string()
+                } else {
+                    format!("> {}", line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{}", content)
+    }
+}
+
+pub fn parse_block_quote(lines: &[&str], start_index: usize) -> (MarkdownBlock, usize) {
+    let mut quote_lines = Vec::new();
+    let mut i = start_index;
+
+    while i < lines.len() {
+        if let Some(caps) = BLOCK_QUOTE_REGEX.captures(lines[i]) {
+            quote_lines.push(caps[1].to_string());
+            i += 1;
+        } else {
+            break;
+        }
+    }
+
+    (
+        MarkdownBlock::BlockQuote(BlockQuote::new(quote_lines)),
+        i - start_index,
+    )
+}
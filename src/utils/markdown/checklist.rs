@@ -1,64 +1,420 @@
+use chrono::{Duration, NaiveDate};
 use regex::Regex;
+use serde::Serialize;
 use std::{fmt, sync::LazyLock};
 
-use crate::utils::markdown::MarkdownBlock;
+use crate::utils::{
+    markdown::{MarkdownBlock, unordered_list::UNORDERED_LIST_REGEX},
+    time::{format_duration, parse_duration},
+};
 
 pub static CHECKLIST_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^\s*[-*+]\s+\[([ xX])\]\s+(.+?)\s*$").unwrap());
+    LazyLock::new(|| Regex::new(r"^(\s*)[-*+]\s+\[([ xX/?>-])\]\s+(.+?)\s*$").unwrap());
 
-#[derive(Debug, Clone)]
+/// Time spent on a task, written as `⏱ 45m` (as `wl stop` does) or `(1h30m)`
+static TRACKED_TIME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*(?:⏱\s*(\d+h\d*m?|\d+m)|\((\d+h\d*m?|\d+m)\))").unwrap());
+
+/// Day a task was first written down, kept at the end of the line as a hidden
+/// `<!-- since:2025-01-10 -->` comment once the task has been carried over
+static SINCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.*?)\s*<!--\s*since:\s*(\d{4}-\d{2}-\d{2})\s*-->$").unwrap());
+
+/// Due date, written as `📅 2025-02-01` or `due:2025-02-01`
+static DUE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*(?:📅\s*|\bdue:)(\d{4}-\d{2}-\d{2})").unwrap());
+
+/// Day a queued task is to be added to the daily notes, written as `⏳ 2025-02-10`
+static SCHEDULED_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*⏳\s*(\d{4}-\d{2}-\d{2})").unwrap());
+
+/// `#tag` words in a task's text
+static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:^|\s)#([\w-]+)").unwrap());
+
+/// Bullet-journal style task states, written as the character between the brackets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    /// `[ ]`
+    Todo,
+    /// `[/]`
+    InProgress,
+    /// `[?]`
+    Blocked,
+    /// `[x]` or `[X]`
+    Done,
+    /// `[-]`
+    Cancelled,
+    /// `[>]` deferred, or migrated to another note
+    Migrated,
+}
+
+impl TaskState {
+    pub fn from_marker(marker: char) -> Option<Self> {
+        match marker {
+            ' ' => Some(TaskState::Todo),
+            '/' => Some(TaskState::InProgress),
+            '?' => Some(TaskState::Blocked),
+            'x' | 'X' => Some(TaskState::Done),
+            '-' => Some(TaskState::Cancelled),
+            '>' => Some(TaskState::Migrated),
+            _ => None,
+        }
+    }
+
+    pub fn marker(&self) -> char {
+        match self {
+            TaskState::Todo => ' ',
+            TaskState::InProgress => '/',
+            TaskState::Blocked => '?',
+            TaskState::Done => 'x',
+            TaskState::Cancelled => '-',
+            TaskState::Migrated => '>',
+        }
+    }
+
+    /// Whether the task still needs doing
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self,
+            TaskState::Todo | TaskState::InProgress | TaskState::Blocked
+        )
+    }
+
+    /// Open tasks are copied into the next daily note; done, cancelled and migrated ones stay behind
+    pub fn carries_over(&self) -> bool {
+        self.is_open()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecklistItem {
+    pub state: TaskState,
+    pub content: String,
+    pub indentation_level: usize,
+    /// Day the task was first written down, when it was carried over from an earlier note
+    pub since: Option<NaiveDate>,
+    /// Plain bullets indented beneath this item, such as links or context, written as
+    /// they were relative to the item's own indentation
+    pub details: Vec<String>,
+    /// Subtasks indented beneath this item
+    pub children: Vec<ChecklistItem>,
+}
+
+impl ChecklistItem {
+    pub fn new(content: String) -> Self {
+        ChecklistItem {
+            state: TaskState::Todo,
+            content,
+            indentation_level: 0,
+            since: None,
+            details: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Day the task was first written down; tasks without a `since` date are taken to be
+    /// new in the note dated `note_date`
+    pub fn origin(&self, note_date: NaiveDate) -> NaiveDate {
+        self.since.unwrap_or(note_date)
+    }
+
+    /// Records `date` as the day this task and its subtasks were first written down,
+    /// unless they already have one
+    pub fn mark_since(&mut self, date: NaiveDate) {
+        self.since.get_or_insert(date);
+        for child in &mut self.children {
+            child.mark_since(date);
+        }
+    }
+
+    /// The task's content without the annotations commands add to it, such as tracked time
+    /// and due dates
+    pub fn text(&self) -> String {
+        [&*TRACKED_TIME_REGEX, &*DUE_REGEX, &*SCHEDULED_REGEX]
+            .iter()
+            .fold(self.content.clone(), |text, regex| {
+                regex.replace_all(&text, "").into_owned()
+            })
+            .trim()
+            .to_string()
+    }
+
+    /// The `📅 2025-02-01` or `due:2025-02-01` date on the task
+    pub fn due(&self) -> Option<NaiveDate> {
+        date_annotation(&DUE_REGEX, &self.content)
+    }
+
+    /// Sets the task's due date, replacing any it already has
+    pub fn set_due(&mut self, due: NaiveDate) {
+        let content = DUE_REGEX.replace_all(&self.content, "");
+        self.content = format!("{} 📅 {}", content.trim_end(), due);
+    }
+
+    /// The `⏳ 2025-02-10` date a queued task is to be added to the daily notes on
+    pub fn scheduled(&self) -> Option<NaiveDate> {
+        date_annotation(&SCHEDULED_REGEX, &self.content)
+    }
+
+    /// Sets or, with `None`, removes the `⏳` date a queued task is to be added on
+    pub fn set_scheduled(&mut self, scheduled: Option<NaiveDate>) {
+        let content = SCHEDULED_REGEX.replace_all(&self.content, "");
+        self.content = match scheduled {
+            Some(scheduled) => format!("{} ⏳ {}", content.trim_end(), scheduled),
+            None => content.trim_end().to_string(),
+        };
+    }
+
+    /// Short identifier derived from the task's text, so it stays the same when the rest
+    /// of the note changes, when time is tracked against it and when it is carried over.
+    /// Tasks with the same text share it; `MarkdownFile::tasks_with_ids` numbers them apart.
+    pub fn id(&self) -> String {
+        let normalized = self
+            .text()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+
+        // FNV-1a, which unlike std's DefaultHasher is guaranteed stable between releases
+        let mut hash: u32 = 0x811c9dc5;
+        for byte in normalized.bytes() {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(0x01000193);
+        }
+        format!("{:05x}", hash & 0xfffff)
+    }
+
+    /// Total of the `⏱ 45m` and `(1h30m)` time annotations on the task
+    pub fn tracked_time(&self) -> Option<Duration> {
+        TRACKED_TIME_REGEX
+            .captures_iter(&self.content)
+            .filter_map(|caps| parse_duration(caps.get(1).or(caps.get(2))?.as_str()))
+            .reduce(|total, duration| total + duration)
+    }
+
+    /// `#tag` words in the task's text, without the `#`
+    pub fn tags(&self) -> Vec<String> {
+        TAG_REGEX
+            .captures_iter(&self.content)
+            .map(|caps| caps[1].to_string())
+            .collect()
+    }
+
+    /// Adds `duration` to the task's tracked time, replacing its time annotations with a
+    /// single `⏱` annotation of the total at the end
+    pub fn add_tracked_time(&mut self, duration: Duration) {
+        let total = self.tracked_time().unwrap_or_default() + duration;
+        let content = TRACKED_TIME_REGEX.replace_all(&self.content, "");
+        self.content = format!("{} ⏱ {}", content.trim_end(), format_duration(total));
+    }
+
+    /// Adds an unchecked subtask one level deeper than this item
+    pub fn add_child(&mut self, content: String) {
+        let mut child = ChecklistItem::new(content);
+        child.indentation_level = self.indentation_level + 1;
+        self.children.push(child);
+    }
+
+    /// Re-indents this item to `level`, keeping its subtasks nested beneath it
+    pub fn set_indentation_level(&mut self, level: usize) {
+        self.indentation_level = level;
+        for child in &mut self.children {
+            child.set_indentation_level(level + 1);
+        }
+    }
+
+    /// Marks this item and its subtasks as migrated if they would carry over
+    pub fn migrate(&mut self) {
+        if self.state.carries_over() {
+            self.state = TaskState::Migrated;
+        }
+        for child in &mut self.children {
+            child.migrate();
+        }
+    }
+
+    /// Flips between done and not done; any other state counts as not done
+    pub fn toggle(&mut self) {
+        self.state = if self.state == TaskState::Done {
+            TaskState::Todo
+        } else {
+            TaskState::Done
+        };
+    }
+
+    /// Removes items that don't carry over, keeping such a parent if it still has subtasks that do.
+    /// Time tracked on earlier days stays in those days' notes.
+    pub fn carry_over(&self) -> Option<ChecklistItem> {
+        let children: Vec<ChecklistItem> = self
+            .children
+            .iter()
+            .filter_map(ChecklistItem::carry_over)
+            .collect();
+
+        if !self.state.carries_over() && children.is_empty() {
+            return None;
+        }
+
+        Some(ChecklistItem {
+            content: TRACKED_TIME_REGEX
+                .replace_all(&self.content, "")
+                .trim_end()
+                .to_string(),
+            children,
+            ..self.clone()
+        })
+    }
+
+    fn write_lines(&self, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(self.indentation_level);
+        let mut line = format!("{}- [{}] {}", indent, self.state.marker(), self.content);
+        if let Some(since) = self.since {
+            line.push_str(&format!(" <!-- since:{} -->", since));
+        }
+        lines.push(line);
+        for detail in &self.details {
+            lines.push(format!("{}{}", indent, detail));
+        }
+
+        for child in &self.children {
+            child.write_lines(lines);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Checklist {
-    pub items: Vec<(bool, String)>,
+    pub items: Vec<ChecklistItem>,
+    pub source: Option<String>,
 }
 
 impl Checklist {
     pub fn new() -> Self {
-        Checklist { items: Vec::new() }
+        Checklist {
+            items: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Every item in document order, paired with its path of child indexes from the top level
+    pub fn iter_items(&self) -> Vec<(Vec<usize>, &ChecklistItem)> {
+        fn walk<'a>(
+            items: &'a [ChecklistItem],
+            path: &mut Vec<usize>,
+            out: &mut Vec<(Vec<usize>, &'a ChecklistItem)>,
+        ) {
+            for (index, item) in items.iter().enumerate() {
+                path.push(index);
+                out.push((path.clone(), item));
+                walk(&item.children, path, out);
+                path.pop();
+            }
+        }
+
+        let mut out = Vec::new();
+        walk(&self.items, &mut Vec::new(), &mut out);
+        out
+    }
+
+    pub fn item_mut(&mut self, path: &[usize]) -> Option<&mut ChecklistItem> {
+        let (first, rest) = path.split_first()?;
+        let mut item = self.items.get_mut(*first)?;
+        for index in rest {
+            item = item.children.get_mut(*index)?;
+        }
+        Some(item)
     }
 
-    // pub fn add_item(&mut self, item: String) {
-    //     self.items.push((false, item));
-    // }
+    /// Removes the item at `path` along with its subtasks
+    pub fn remove_item(&mut self, path: &[usize]) -> Option<ChecklistItem> {
+        let (index, parent_path) = path.split_last()?;
+        let siblings = self.siblings_mut(parent_path)?;
+        (*index < siblings.len()).then(|| siblings.remove(*index))
+    }
+
+    /// Swaps the item at `path` with its previous (`up`) or next sibling, returning its new path
+    pub fn move_item(&mut self, path: &[usize], up: bool) -> Option<Vec<usize>> {
+        let (index, parent_path) = path.split_last()?;
+        let siblings = self.siblings_mut(parent_path)?;
+        let new_index = if up {
+            index.checked_sub(1)?
+        } else {
+            Some(index + 1).filter(|new_index| *new_index < siblings.len())?
+        };
+        siblings.swap(*index, new_index);
 
-    // pub fn toggle_item(&mut self, index: usize) {
-    //     if let Some((checked, _)) = self.items.get_mut(index) {
-    //         *checked = !*checked;
-    //     }
-    // }
+        let mut new_path = parent_path.to_vec();
+        new_path.push(new_index);
+        Some(new_path)
+    }
+
+    fn siblings_mut(&mut self, parent_path: &[usize]) -> Option<&mut Vec<ChecklistItem>> {
+        if parent_path.is_empty() {
+            Some(&mut self.items)
+        } else {
+            Some(&mut self.item_mut(parent_path)?.children)
+        }
+    }
+
+    pub fn toggle_item(&mut self, path: &[usize]) {
+        if let Some(item) = self.item_mut(path) {
+            item.toggle();
+        }
+    }
 }
 
 impl fmt::Display for Checklist {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let content = self
-            .items
-            .iter()
-            .map(|(checked, item)| {
-                let checkbox = if *checked { "[x]" } else { "[ ]" };
-                format!("- {} {}", checkbox, item)
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-        write!(f, "{}", content)
+        let mut lines = Vec::new();
+        for item in &self.items {
+            item.write_lines(&mut lines);
+        }
+        write!(f, "{}", lines.join("\n"))
     }
 }
 
 pub fn parse_checklist(lines: &[&str], start_index: usize) -> (MarkdownBlock, usize) {
-    let mut items = Vec::new();
+    let mut flat_items = Vec::new();
     let mut i = start_index;
 
     while i < lines.len() {
-        let line = lines[i].trim();
+        let line = lines[i];
 
-        if line.is_empty() {
+        if line.trim().is_empty() {
             i += 1;
             continue;
         }
 
         if let Some(caps) = CHECKLIST_REGEX.captures(line) {
-            let checkbox = &caps[1];
-            let checked = checkbox == "x" || checkbox == "X";
-            let content = caps[2].to_string();
-            items.push((checked, content));
+            // Convert tabs to equivalent spaces (1 tab = 4 spaces) and count indentation
+            let normalized_whitespace = caps[1].replace('\t', "    ");
+            let marker = caps[2].chars().next().unwrap_or(' ');
+            let (content, since) = split_since(&caps[3]);
+            flat_items.push(ChecklistItem {
+                state: TaskState::from_marker(marker).unwrap_or(TaskState::Todo),
+                content,
+                indentation_level: normalized_whitespace.len() / 2, // 2 spaces per indentation level
+                since,
+                details: Vec::new(),
+                children: Vec::new(),
+            });
+            i += 1;
+        } else if let Some(caps) = UNORDERED_LIST_REGEX.captures(line) {
+            // A plain bullet indented under an item belongs to it rather than ending the list
+            let normalized_whitespace = caps[1].replace('\t', "    ");
+            let indentation_level = normalized_whitespace.len() / 2;
+            let Some(owner) = flat_items
+                .iter_mut()
+                .rev()
+                .find(|item| item.indentation_level < indentation_level)
+            else {
+                break;
+            };
+            let relative_indent = "  ".repeat(indentation_level - owner.indentation_level);
+            owner
+                .details
+                .push(format!("{}- {}", relative_indent, &caps[2]));
             i += 1;
         } else {
             break;
@@ -66,7 +422,57 @@ pub fn parse_checklist(lines: &[&str], start_index: usize) -> (MarkdownBlock, us
     }
 
     (
-        MarkdownBlock::Checklist(Checklist { items }),
+        MarkdownBlock::Checklist(Checklist {
+            items: nest_items(flat_items),
+            source: None,
+        }),
         i - start_index,
     )
 }
+
+fn date_annotation(regex: &Regex, content: &str) -> Option<NaiveDate> {
+    let caps = regex.captures(content)?;
+    NaiveDate::parse_from_str(&caps[1], "%Y-%m-%d").ok()
+}
+
+/// Separates a trailing `<!-- since:YYYY-MM-DD -->` comment from a task's content
+fn split_since(content: &str) -> (String, Option<NaiveDate>) {
+    SINCE_REGEX
+        .captures(content)
+        .and_then(|caps| {
+            let since = NaiveDate::parse_from_str(&caps[2], "%Y-%m-%d").ok()?;
+            Some((caps[1].to_string(), Some(since)))
+        })
+        .unwrap_or_else(|| (content.to_string(), None))
+}
+
+/// Builds the subtask tree, attaching each item to the closest preceding item indented less than it
+fn nest_items(flat_items: Vec<ChecklistItem>) -> Vec<ChecklistItem> {
+    let mut roots: Vec<ChecklistItem> = Vec::new();
+    // Chain of currently open ancestors, from the top level down
+    let mut stack: Vec<ChecklistItem> = Vec::new();
+
+    for item in flat_items {
+        while stack
+            .last()
+            .is_some_and(|parent| parent.indentation_level >= item.indentation_level)
+        {
+            close_item(&mut stack, &mut roots);
+        }
+        stack.push(item);
+    }
+    while !stack.is_empty() {
+        close_item(&mut stack, &mut roots);
+    }
+
+    roots
+}
+
+fn close_item(stack: &mut Vec<ChecklistItem>, roots: &mut Vec<ChecklistItem>) {
+    if let Some(item) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(item),
+            None => roots.push(item),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use regex::Regex;
+use std::{fmt, sync::LazyLock};
+
+use crate::utils::markdown::MarkdownBlock;
+
+pub static CODE_FENCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(`{3,}|~{3,})\s*([^`\s]*)[^`]*$").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeBlock {
+    pub lang: Option<String>,
+    pub body: String,
+    pub source: Option<String>,
+}
+
+impl CodeBlock {
+    pub fn new(lang: Option<String>, body: String) -> Self {
+        CodeBlock {
+            lang,
+            body,
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for CodeBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Use a fence longer than any backtick run inside the body so it can't close early
+        let longest_run = self
+            .body
+            .lines()
+            .map(|line| line.trim_start().chars().take_while(|c| *c == '`').count())
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest_run.max(2) + 1);
+        let lang = self.lang.as_deref().unwrap_or("");
+
+        if self.body.is_empty() {
+            write!(f, "{}{}\n{}", fence, lang, fence)
+        } else {
+            write!(f, "{}{}\n{}\n{}", fence, lang, self.body, fence)
+        }
+    }
+}
+
+pub fn parse_code_block(lines: &[&str], start_index: usize) -> (MarkdownBlock, usize) {
+    let caps = CODE_FENCE_REGEX
+        .captures(lines[start_index])
+        .expect("parse_code_block called on a line that is not a code fence");
+    let fence = caps[1].to_string();
+    let lang = Some(caps[2].to_string()).filter(|lang| !lang.is_empty());
+
+    let mut body_lines = Vec::new();
+    let mut i = start_index + 1;
+
+    // An unterminated fence runs to the end of the file
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        if is_closing_fence(line, &fence) {
+            break;
+        }
+        body_lines.push(line);
+    }
+
+    (
+        MarkdownBlock::CodeBlock(CodeBlock::new(lang, body_lines.join("\n"))),
+        i - start_index,
+    )
+}
+
+fn is_closing_fence(line: &str, fence: &str) -> bool {
+    let line = line.trim();
+    let fence_char = fence.chars().next().unwrap_or('`');
+    line.len() >= fence.len() && line.chars().all(|c| c == fence_char)
+}
@@ -6,15 +6,20 @@ use crate::utils::markdown::MarkdownBlock;
 pub static HEADING_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\s*(#{1,6})\s+(.+?)\s*$").unwrap());
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Heading {
     pub level: u32,
     pub content: String,
+    pub source: Option<String>,
 }
 
 impl Heading {
     pub fn new(level: u32, content: String) -> Self {
-        Heading { level, content }
+        Heading {
+            level,
+            content,
+            source: None,
+        }
     }
 
     // pub fn replace(&mut self, new_content: String) {
@@ -33,5 +38,5 @@ pub fn parse_heading(line: &str) -> Option<MarkdownBlock> {
     let level = caps[1].len() as u32;
     let content = caps[2].to_string();
 
-    Some(MarkdownBlock::Heading(Heading { level, content }))
+    Some(MarkdownBlock::Heading(Heading::new(level, content)))
 }
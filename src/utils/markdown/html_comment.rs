@@ -0,0 +1,54 @@
+use std::fmt;
+
+use crate::utils::markdown::MarkdownBlock;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlComment {
+    /// Text between `<!--` and `-->`, without surrounding whitespace
+    pub content: String,
+    pub source: Option<String>,
+}
+
+impl HtmlComment {
+    pub fn new(content: String) -> Self {
+        HtmlComment {
+            content,
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for HtmlComment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<!-- {} -->", self.content)
+    }
+}
+
+pub fn is_html_comment_start(line: &str) -> bool {
+    line.trim_start().starts_with("<!--")
+}
+
+pub fn parse_html_comment(lines: &[&str], start_index: usize) -> (MarkdownBlock, usize) {
+    let mut comment_lines = Vec::new();
+    let mut i = start_index;
+
+    // An unterminated comment runs to the end of the file
+    while i < lines.len() {
+        let line = lines[i];
+        comment_lines.push(line.trim());
+        i += 1;
+
+        if line.contains("-->") {
+            break;
+        }
+    }
+
+    let text = comment_lines.join("\n");
+    let text = text.trim_start().trim_start_matches("<!--");
+    let text = text.split("-->").next().unwrap_or_default();
+
+    (
+        MarkdownBlock::HtmlComment(HtmlComment::new(text.trim().to_string())),
+        i - start_index,
+    )
+}
@@ -1,18 +1,83 @@
 use std::{fmt, fs, path::PathBuf};
 
+use chrono::NaiveDate;
+
+pub mod block_quote;
 pub mod checklist;
+pub mod code_block;
 pub mod heading;
+pub mod html_comment;
 pub mod ordered_list;
 pub mod paragraph;
+pub mod table;
+pub mod thematic_break;
 pub mod unordered_list;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum MarkdownBlock {
     Heading(heading::Heading),
     Paragraph(paragraph::Paragraph),
     UnorderedList(unordered_list::UnorderedList),
     OrderedList(ordered_list::OrderedList),
     Checklist(checklist::Checklist),
+    CodeBlock(code_block::CodeBlock),
+    BlockQuote(block_quote::BlockQuote),
+    Table(table::Table),
+    ThematicBreak(thematic_break::ThematicBreak),
+    HtmlComment(html_comment::HtmlComment),
+}
+
+impl MarkdownBlock {
+    /// The exact text this block was parsed from, including the blank lines after it.
+    ///
+    /// Every block type keeps this in a `source` field, set while parsing and `None` for
+    /// blocks built in code. A block is written back as its source for as long as the
+    /// source still parses to an equal block, so untouched parts of a note keep their
+    /// original formatting; once edited, the block is rendered from its fields instead.
+    pub fn source(&self) -> Option<&str> {
+        match self {
+            MarkdownBlock::Heading(heading) => heading.source.as_deref(),
+            MarkdownBlock::Paragraph(paragraph) => paragraph.source.as_deref(),
+            MarkdownBlock::UnorderedList(list) => list.source.as_deref(),
+            MarkdownBlock::OrderedList(list) => list.source.as_deref(),
+            MarkdownBlock::Checklist(checklist) => checklist.source.as_deref(),
+            MarkdownBlock::CodeBlock(code) => code.source.as_deref(),
+            MarkdownBlock::BlockQuote(quote) => quote.source.as_deref(),
+            MarkdownBlock::Table(table) => table.source.as_deref(),
+            MarkdownBlock::ThematicBreak(thematic_break) => thematic_break.source.as_deref(),
+            MarkdownBlock::HtmlComment(comment) => comment.source.as_deref(),
+        }
+    }
+
+    fn with_source(mut self, source: String) -> Self {
+        let slot = match &mut self {
+            MarkdownBlock::Heading(heading) => &mut heading.source,
+            MarkdownBlock::Paragraph(paragraph) => &mut paragraph.source,
+            MarkdownBlock::UnorderedList(list) => &mut list.source,
+            MarkdownBlock::OrderedList(list) => &mut list.source,
+            MarkdownBlock::Checklist(checklist) => &mut checklist.source,
+            MarkdownBlock::CodeBlock(code) => &mut code.source,
+            MarkdownBlock::BlockQuote(quote) => &mut quote.source,
+            MarkdownBlock::Table(table) => &mut table.source,
+            MarkdownBlock::ThematicBreak(thematic_break) => &mut thematic_break.source,
+            MarkdownBlock::HtmlComment(comment) => &mut comment.source,
+        };
+        *slot = Some(source);
+        self
+    }
+
+    /// Returns the source text if the block still parses back from it unchanged.
+    ///
+    /// Blocks are freely cloned and edited by commands, so rather than tracking
+    /// mutations we re-parse the source and compare it against the current block.
+    fn unmodified_source(&self) -> Option<&str> {
+        let source = self.source()?;
+        let reparsed = MarkdownFile::from_string(source);
+        match reparsed.blocks.as_slice() {
+            [block] if block == self => Some(source),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for MarkdownBlock {
@@ -23,16 +88,31 @@ impl fmt::Display for MarkdownBlock {
             MarkdownBlock::UnorderedList(list) => list.fmt(f),
             MarkdownBlock::OrderedList(list) => list.fmt(f),
             MarkdownBlock::Checklist(checklist) => checklist.fmt(f),
+            MarkdownBlock::CodeBlock(code) => code.fmt(f),
+            MarkdownBlock::BlockQuote(quote) => quote.fmt(f),
+            MarkdownBlock::Table(table) => table.fmt(f),
+            MarkdownBlock::ThematicBreak(thematic_break) => thematic_break.fmt(f),
+            MarkdownBlock::HtmlComment(comment) => comment.fmt(f),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MarkdownFile {
     pub blocks: Vec<MarkdownBlock>,
+    /// Blank lines before the first block (or the whole file if it has no blocks)
+    preamble: String,
 }
 
 impl MarkdownFile {
+    /// A file made of new blocks, e.g. a generated report
+    pub fn new(blocks: Vec<MarkdownBlock>) -> Self {
+        MarkdownFile {
+            blocks,
+            preamble: String::new(),
+        }
+    }
+
     pub fn from_path(file_path: &PathBuf) -> Result<Self, std::io::Error> {
         let content = fs::read_to_string(file_path)?;
         Ok(Self::from_string(&content))
@@ -40,58 +120,93 @@ impl MarkdownFile {
 
     pub fn from_string(content: &str) -> Self {
         let lines: Vec<&str> = content.lines().collect();
+        // Same lines with their line endings kept, used to slice out each block's source
+        let raw_lines: Vec<&str> = content.split_inclusive('\n').collect();
 
         let mut blocks = Vec::new();
         let mut i = 0;
 
+        // Leading empty lines belong to the file rather than any block
+        while i < lines.len() && lines[i].trim().is_empty() {
+            i += 1;
+        }
+        let preamble = raw_lines[..i].concat();
+
         while i < lines.len() {
+            let start = i;
             let line = lines[i].trim();
 
-            // Skip empty lines
-            if line.is_empty() {
-                i += 1;
-                continue;
+            // Check for fenced code block (must come first, its body is not parsed)
+            let block = if code_block::CODE_FENCE_REGEX.is_match(line) {
+                let (code_block, consumed) = code_block::parse_code_block(&lines, i);
+                i += consumed;
+                code_block
+            }
+            // Check for HTML comment
+            else if html_comment::is_html_comment_start(line) {
+                let (comment_block, consumed) = html_comment::parse_html_comment(&lines, i);
+                i += consumed;
+                comment_block
             }
-
             // Check for heading
-            if heading::HEADING_REGEX.is_match(line) {
-                if let Some(heading_block) = heading::parse_heading(line) {
-                    blocks.push(heading_block);
-                } else {
+            else if heading::HEADING_REGEX.is_match(line) {
+                i += 1;
+                heading::parse_heading(line).unwrap_or_else(|| {
                     // Fallback to paragraph if parsing fails
-                    blocks.push(MarkdownBlock::Paragraph(paragraph::Paragraph {
-                        content: line.to_string(),
-                    }));
-                }
+                    MarkdownBlock::Paragraph(paragraph::Paragraph::new(line.to_string()))
+                })
+            }
+            // Check for thematic break (must come before list checks, `- - -` looks like a list)
+            else if thematic_break::THEMATIC_BREAK_REGEX.is_match(line) {
                 i += 1;
+                MarkdownBlock::ThematicBreak(thematic_break::ThematicBreak::new())
             }
             // Check for checklist (must come before unordered list check)
             else if checklist::CHECKLIST_REGEX.is_match(line) {
                 let (checklist_block, consumed) = checklist::parse_checklist(&lines, i);
-                blocks.push(checklist_block);
                 i += consumed;
+                checklist_block
             }
             // Check for unordered list
             else if unordered_list::UNORDERED_LIST_REGEX.is_match(line) {
                 let (list_block, consumed) = unordered_list::parse_unordered_list(&lines, i);
-                blocks.push(list_block);
                 i += consumed;
+                list_block
             }
             // Check for ordered list
             else if ordered_list::ORDERED_LIST_REGEX.is_match(line) {
                 let (list_block, consumed) = ordered_list::parse_ordered_list(&lines, i);
-                blocks.push(list_block);
                 i += consumed;
+                list_block
+            }
+            // Check for block quote
+            else if block_quote::BLOCK_QUOTE_REGEX.is_match(line) {
+                let (quote_block, consumed) = block_quote::parse_block_quote(&lines, i);
+                i += consumed;
+                quote_block
+            }
+            // Check for table
+            else if table::is_table_start(&lines, i) {
+                let (table_block, consumed) = table::parse_table(&lines, i);
+                i += consumed;
+                table_block
             }
             // Default to paragraph - collect consecutive non-markdown lines
             else {
                 let (paragraph_block, consumed) = Self::parse_paragraph(&lines, i);
-                blocks.push(paragraph_block);
                 i += consumed;
+                paragraph_block
+            };
+
+            // Blank lines after a block are kept with it so they can be written back as-is
+            while i < lines.len() && lines[i].trim().is_empty() {
+                i += 1;
             }
+
+            blocks.push(block.with_source(raw_lines[start..i].concat()));
         }
 
-        MarkdownFile { blocks }
+        MarkdownFile { blocks, preamble }
     }
 
     fn parse_paragraph(lines: &[&str], start_index: usize) -> (MarkdownBlock, usize) {
@@ -111,6 +226,11 @@ impl MarkdownFile {
                 || checklist::CHECKLIST_REGEX.is_match(line)
                 || unordered_list::UNORDERED_LIST_REGEX.is_match(line)
                 || ordered_list::ORDERED_LIST_REGEX.is_match(line)
+                || code_block::CODE_FENCE_REGEX.is_match(line)
+                || html_comment::is_html_comment_start(line)
+                || thematic_break::THEMATIC_BREAK_REGEX.is_match(line)
+                || block_quote::BLOCK_QUOTE_REGEX.is_match(line)
+                || table::is_table_start(lines, i)
             {
                 break;
             }
@@ -121,55 +241,52 @@ impl MarkdownFile {
 
         let content = paragraph_lines.join(" ");
         (
-            MarkdownBlock::Paragraph(paragraph::Paragraph { content }),
+            MarkdownBlock::Paragraph(paragraph::Paragraph::new(content)),
             i - start_index,
         )
     }
 
-    pub fn to_string(&self) -> String {
-        self.blocks
-            .iter()
-            .map(|block| block.to_string())
-            .collect::<Vec<_>>()
-            .join("\n\n")
-    }
-
     // pub fn to_vec(&self) -> Vec<String> {
     //     self.blocks.iter().map(|block| block.to_string()).collect()
     // }
 
     pub fn set_title(&self, new_title: &str) -> MarkdownFile {
-        let new_heading = MarkdownBlock::Heading(heading::Heading::new(1, new_title.to_string()));
-        let mut new_blocks = self.blocks.clone();
+        let mut new_file = self.clone();
 
-        // todo: probably a cleaner way to check if the first block is a heading
-        if let Some(MarkdownBlock::Heading(_)) = self.blocks.get(0) {
-            new_blocks[0] = new_heading;
+        // Keep the old heading's source so the spacing after the title is preserved
+        if let Some(MarkdownBlock::Heading(heading)) = self.blocks.first() {
+            let mut new_heading = heading.clone();
+            new_heading.level = 1;
+            new_heading.content = new_title.to_string();
+            new_file.blocks[0] = MarkdownBlock::Heading(new_heading);
         } else {
-            new_blocks.insert(0, new_heading);
+            let new_heading = heading::Heading::new(1, new_title.to_string());
+            new_file
+                .blocks
+                .insert(0, MarkdownBlock::Heading(new_heading));
         }
 
-        MarkdownFile { blocks: new_blocks }
+        new_file
     }
 
     pub fn filter_completed_tasks(&self) -> MarkdownFile {
         let mut new_blocks = Vec::new();
-        
+
         for block in &self.blocks {
             match block {
                 MarkdownBlock::Checklist(checklist) => {
-                    // Filter out completed tasks
-                    let uncompleted_items: Vec<(bool, String)> = checklist
+                    // Filter out tasks that don't carry over, keeping parents that still have open subtasks
+                    let uncompleted_items: Vec<checklist::ChecklistItem> = checklist
                         .items
                         .iter()
-                        .filter(|(completed, _)| !completed)
-                        .cloned()
+                        .filter_map(checklist::ChecklistItem::carry_over)
                         .collect();
-                    
+
                     // Only add the checklist if it has uncompleted items
                     if !uncompleted_items.is_empty() {
                         new_blocks.push(MarkdownBlock::Checklist(checklist::Checklist {
                             items: uncompleted_items,
+                            source: checklist.source.clone(),
                         }));
                     }
                 }
@@ -180,6 +297,458 @@ impl MarkdownFile {
             }
         }
 
-        MarkdownFile { blocks: new_blocks }
+        MarkdownFile {
+            blocks: new_blocks,
+            preamble: self.preamble.clone(),
+        }
+    }
+
+    /// Records `date` as the day each task was first written down, for tasks that don't
+    /// have one yet; used when carrying tasks over from the note dated `date`
+    pub fn mark_tasks_since(&mut self, date: NaiveDate) {
+        for block in &mut self.blocks {
+            if let MarkdownBlock::Checklist(checklist) = block {
+                for item in &mut checklist.items {
+                    item.mark_since(date);
+                }
+            }
+        }
+    }
+
+    /// Marks every task that would carry over as migrated (`[>]`), returning how many were
+    pub fn migrate_open_tasks(&mut self) -> usize {
+        let top_level: Vec<TaskRef> = self
+            .tasks()
+            .into_iter()
+            .filter(|(task_ref, _)| task_ref.path.len() == 1)
+            .map(|(task_ref, _)| task_ref)
+            .collect();
+        self.migrate_tasks(&top_level)
+    }
+
+    /// Marks the tasks at `refs` and their subtasks as migrated (`[>]`) where they would
+    /// carry over, returning how many were
+    pub fn migrate_tasks(&mut self, refs: &[TaskRef]) -> usize {
+        let mut migrated = 0;
+        for task_ref in refs {
+            if let Some(item) = self.task_mut(task_ref) {
+                let before = item.clone();
+                item.migrate();
+                migrated += count_open(&before);
+            }
+        }
+        migrated
+    }
+
+    /// Adds the top level tasks of `other` that carry over, with their open subtasks, under
+    /// the headings of the same name in this file. A task is skipped when this file already
+    /// had one like it under that heading before the merge, each of which stands in for one
+    /// task of `other`. Headings this file is missing are added below the same parent
+    /// heading if it has one, or else at the end. Tasks under `other`'s title go under this
+    /// file's title.
+    pub fn merge_tasks(&mut self, other: &MarkdownFile) -> MergedTasks {
+        let mut merged = MergedTasks::default();
+        // Section and ID of each task, whether it's open, and whether a task of `other`
+        // has been matched to it yet
+        let mut existing: Vec<(Option<String>, String, bool, bool)> = self
+            .tasks()
+            .iter()
+            .map(|(task_ref, item)| {
+                (
+                    self.section_name(task_ref.block_index),
+                    item.id(),
+                    item.state.carries_over(),
+                    false,
+                )
+            })
+            .collect();
+
+        for (task_ref, item) in other.tasks() {
+            if task_ref.path.len() > 1 {
+                continue;
+            }
+            let Some(item) = item.carry_over() else {
+                continue;
+            };
+            let section = other.section_name(task_ref.block_index);
+            let id = item.id();
+            if let Some((_, _, open, matched)) =
+                existing
+                    .iter_mut()
+                    .find(|(own_section, own_id, _, matched)| {
+                        !*matched && *own_section == section && *own_id == id
+                    })
+            {
+                *matched = true;
+                if *open {
+                    merged.already_open.push(task_ref.clone());
+                }
+                continue;
+            }
+            merged.tasks.push(task_ref.clone());
+
+            let heading_index = match other.heading_index_for(task_ref.block_index) {
+                Some(index) if other.heading_level(index) > 1 => {
+                    let MarkdownBlock::Heading(heading) = &other.blocks[index] else {
+                        unreachable!("heading_index_for only returns headings");
+                    };
+                    if self.find_heading(&heading.content).is_none() {
+                        merged.headings.push(heading.content.clone());
+                    }
+                    let parent = other
+                        .parent_heading(index)
+                        .filter(|parent| parent.level > 1)
+                        .map(|parent| parent.content.as_str());
+                    self.find_or_insert_heading(&heading.content, parent)
+                }
+                _ => match self.blocks.iter().position(
+                    |block| matches!(block, MarkdownBlock::Heading(heading) if heading.level == 1),
+                ) {
+                    Some(index) => index,
+                    None => {
+                        self.prepend_untitled_task(item);
+                        continue;
+                    }
+                },
+            };
+            self.append_task(heading_index, item);
+        }
+
+        merged
+    }
+
+    /// Lowercased name of the section the block at `block_index` is in, or `None` when it's
+    /// under the title or no heading at all
+    fn section_name(&self, block_index: usize) -> Option<String> {
+        self.heading_index_for(block_index)
+            .filter(|index| self.heading_level(*index) > 1)
+            .and_then(|index| match &self.blocks[index] {
+                MarkdownBlock::Heading(heading) => Some(heading.content.to_lowercase()),
+                _ => None,
+            })
+    }
+
+    /// Every checklist item in the file, in document order
+    pub fn tasks(&self) -> Vec<(TaskRef, &checklist::ChecklistItem)> {
+        let mut tasks = Vec::new();
+        for (block_index, block) in self.blocks.iter().enumerate() {
+            if let MarkdownBlock::Checklist(checklist) = block {
+                for (path, item) in checklist.iter_items() {
+                    tasks.push((TaskRef { block_index, path }, item));
+                }
+            }
+        }
+        tasks
+    }
+
+    /// Every task paired with the ID commands know it by in this file: its own `id()`,
+    /// numbered `b1ef1-2`, `b1ef1-3` and so on for later tasks with the same text
+    pub fn tasks_with_ids(&self) -> Vec<(TaskRef, &checklist::ChecklistItem, String)> {
+        let mut seen: Vec<(String, usize)> = Vec::new();
+        self.tasks()
+            .into_iter()
+            .map(|(task_ref, item)| {
+                let id = item.id();
+                let id = match seen.iter_mut().find(|(seen_id, _)| *seen_id == id) {
+                    Some((_, count)) => {
+                        *count += 1;
+                        format!("{}-{}", id, count)
+                    }
+                    None => {
+                        seen.push((id.clone(), 1));
+                        id
+                    }
+                };
+                (task_ref, item, id)
+            })
+            .collect()
+    }
+
+    /// The closest heading above the block at `block_index`
+    pub fn heading_for(&self, block_index: usize) -> Option<&heading::Heading> {
+        self.blocks[..block_index]
+            .iter()
+            .rev()
+            .find_map(|block| match block {
+                MarkdownBlock::Heading(heading) => Some(heading),
+                _ => None,
+            })
+    }
+
+    /// Adds a task to the checklist at the very top of a file without a title
+    fn prepend_untitled_task(&mut self, mut item: checklist::ChecklistItem) {
+        item.set_indentation_level(0);
+        match self.blocks.first_mut() {
+            Some(MarkdownBlock::Checklist(checklist)) => checklist.items.push(item),
+            _ => {
+                let mut checklist = checklist::Checklist::new();
+                checklist.items.push(item);
+                self.blocks.insert(0, MarkdownBlock::Checklist(checklist));
+            }
+        }
+    }
+
+    fn heading_index_for(&self, block_index: usize) -> Option<usize> {
+        (0..block_index)
+            .rev()
+            .find(|index| matches!(self.blocks[*index], MarkdownBlock::Heading(_)))
+    }
+
+    fn heading_level(&self, index: usize) -> u32 {
+        match &self.blocks[index] {
+            MarkdownBlock::Heading(heading) => heading.level,
+            _ => 0,
+        }
+    }
+
+    /// The closest heading above the heading at `heading_index` with a lower level
+    fn parent_heading(&self, heading_index: usize) -> Option<&heading::Heading> {
+        let level = self.heading_level(heading_index);
+        self.blocks[..heading_index]
+            .iter()
+            .rev()
+            .find_map(|block| match block {
+                MarkdownBlock::Heading(heading) if heading.level < level => Some(heading),
+                _ => None,
+            })
+    }
+
+    pub fn task_mut(&mut self, task: &TaskRef) -> Option<&mut checklist::ChecklistItem> {
+        match self.blocks.get_mut(task.block_index) {
+            Some(MarkdownBlock::Checklist(checklist)) => checklist.item_mut(&task.path),
+            _ => None,
+        }
+    }
+
+    /// Removes a task and its subtasks, dropping its checklist block if that leaves it empty.
+    ///
+    /// Removing a block shifts the indexes of everything after it, so any other
+    /// `TaskRef`s into this file should be looked up again afterwards.
+    pub fn remove_task(&mut self, task: &TaskRef) -> Option<checklist::ChecklistItem> {
+        let Some(MarkdownBlock::Checklist(checklist)) = self.blocks.get_mut(task.block_index)
+        else {
+            return None;
+        };
+        let item = checklist.remove_item(&task.path)?;
+        if checklist.items.is_empty() {
+            self.blocks.remove(task.block_index);
+        }
+        Some(item)
+    }
+
+    /// Appends a top level task to the section under the heading at `heading_index`,
+    /// adding to the section's last checklist or starting one right below the heading
+    pub fn append_task(
+        &mut self,
+        heading_index: usize,
+        mut item: checklist::ChecklistItem,
+    ) -> TaskRef {
+        item.set_indentation_level(0);
+
+        let section_end = self.section_end(heading_index);
+        let existing_checklist = (heading_index + 1..section_end)
+            .rev()
+            .find(|index| matches!(self.blocks[*index], MarkdownBlock::Checklist(_)));
+
+        match existing_checklist {
+            Some(block_index) => {
+                let MarkdownBlock::Checklist(checklist) = &mut self.blocks[block_index] else {
+                    unreachable!("block was just matched as a checklist");
+                };
+                checklist.items.push(item);
+                TaskRef {
+                    block_index,
+                    path: vec![checklist.items.len() - 1],
+                }
+            }
+            None => {
+                let mut checklist = checklist::Checklist::new();
+                checklist.items.push(item);
+                let block_index = heading_index + 1;
+                self.blocks
+                    .insert(block_index, MarkdownBlock::Checklist(checklist));
+                TaskRef {
+                    block_index,
+                    path: vec![0],
+                }
+            }
+        }
+    }
+
+    /// Appends a top level item, followed by `children` nested one level beneath it, to the
+    /// section under the heading at `heading_index`. Adds to the section's last list (in
+    /// time order, for timestamped items) or starts one at the end of the section.
+    pub fn append_list_item(
+        &mut self,
+        heading_index: usize,
+        mut item: unordered_list::UnorderedListItem,
+        mut children: Vec<unordered_list::UnorderedListItem>,
+    ) {
+        item.indentation_level = 0;
+        for child in &mut children {
+            child.indentation_level = 1;
+        }
+
+        let section_end = self.section_end(heading_index);
+        let existing_list = (heading_index + 1..section_end)
+            .rev()
+            .find(|index| matches!(self.blocks[*index], MarkdownBlock::UnorderedList(_)));
+
+        match existing_list {
+            Some(block_index) => {
+                let MarkdownBlock::UnorderedList(list) = &mut self.blocks[block_index] else {
+                    unreachable!("block was just matched as a list");
+                };
+                list.push_in_time_order(item, children);
+            }
+            None => {
+                let mut list = unordered_list::UnorderedList::new();
+                list.push_in_time_order(item, children);
+                self.blocks
+                    .insert(section_end, MarkdownBlock::UnorderedList(list));
+            }
+        }
+    }
+
+    /// Index just past the last block belonging to the heading at `heading_index`,
+    /// i.e. the next heading of any level or the end of the file
+    pub fn section_end(&self, heading_index: usize) -> usize {
+        self.blocks
+            .iter()
+            .enumerate()
+            .skip(heading_index + 1)
+            .find(|(_, block)| matches!(block, MarkdownBlock::Heading(_)))
+            .map_or(self.blocks.len(), |(index, _)| index)
+    }
+
+    /// Index just past the heading at `heading_index` and everything nested under it,
+    /// i.e. the next heading of the same or a higher level or the end of the file
+    pub fn subtree_end(&self, heading_index: usize) -> usize {
+        let Some(MarkdownBlock::Heading(heading)) = self.blocks.get(heading_index) else {
+            return self.section_end(heading_index);
+        };
+        self.blocks
+            .iter()
+            .enumerate()
+            .skip(heading_index + 1)
+            .find(|(_, block)| {
+                matches!(block, MarkdownBlock::Heading(other) if other.level <= heading.level)
+            })
+            .map_or(self.blocks.len(), |(index, _)| index)
+    }
+
+    /// Index of the first heading whose text is `name`, ignoring case
+    pub fn find_heading(&self, name: &str) -> Option<usize> {
+        let name = name.trim();
+        self.blocks.iter().position(|block| {
+            matches!(block, MarkdownBlock::Heading(heading) if heading.content.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Finds the heading named `name`, adding it when missing: one level below the heading
+    /// named `parent` at the end of its subsections, or else as a level 2 heading at the end of the file
+    pub fn find_or_insert_heading(&mut self, name: &str, parent: Option<&str>) -> usize {
+        if let Some(index) = self.find_heading(name) {
+            return index;
+        }
+
+        let parent_index = parent.and_then(|parent| self.find_heading(parent));
+        let (index, level) = match parent_index {
+            Some(parent_index) => {
+                let MarkdownBlock::Heading(parent) = &self.blocks[parent_index] else {
+                    unreachable!("find_heading only returns headings");
+                };
+                (self.subtree_end(parent_index), parent.level + 1)
+            }
+            None => (self.blocks.len(), 2),
+        };
+
+        self.blocks.insert(
+            index,
+            MarkdownBlock::Heading(heading::Heading::new(level.min(6), name.trim().to_string())),
+        );
+        index
+    }
+
+    /// Writes the file by way of a temporary file, so a failed write never leaves it half written
+    pub fn save(&self, file_path: &PathBuf) -> Result<(), std::io::Error> {
+        let mut temp_path = file_path.clone().into_os_string();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, self.to_string())?;
+        fs::rename(&temp_path, file_path)
+    }
+}
+
+/// What `MarkdownFile::merge_tasks` added
+#[derive(Debug, Default, PartialEq)]
+pub struct MergedTasks {
+    /// The top level tasks of the other file that were added, with their subtasks
+    pub tasks: Vec<TaskRef>,
+    /// The top level tasks of the other file skipped because this file has them open already
+    pub already_open: Vec<TaskRef>,
+    /// Names of the headings that were missing and added for them
+    pub headings: Vec<String>,
+}
+
+/// Location of a checklist item: the checklist block, then child indexes down to the item
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskRef {
+    pub block_index: usize,
+    pub path: Vec<usize>,
+}
+
+/// How many of `item` and its subtasks would carry over
+fn count_open(item: &checklist::ChecklistItem) -> usize {
+    usize::from(item.state.carries_over()) + item.children.iter().map(count_open).sum::<usize>()
+}
+
+/// Returns the line ending and blank lines that follow the last line of content in `source`
+fn trailing_blank_lines(source: &str) -> &str {
+    let content_end = source.trim_end().len();
+    let line_end = source[content_end..]
+        .find('\n')
+        .map_or(source.len(), |offset| content_end + offset);
+    &source[line_end..]
+}
+
+/// Renders the file, writing unmodified blocks back exactly as they were read and
+/// only re-rendering blocks that were added or changed.
+impl fmt::Display for MarkdownFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut content = self.preamble.clone();
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            // The previous block may have been the last line of a file without a newline
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+
+            if let Some(source) = block.unmodified_source() {
+                content.push_str(source);
+                continue;
+            }
+
+            match block.source() {
+                // Edited blocks keep the spacing they had after them
+                Some(source) => {
+                    content.push_str(&block.to_string());
+                    content.push_str(trailing_blank_lines(source));
+                }
+                // New blocks are separated from their neighbours by a blank line
+                None => {
+                    if !content.is_empty() && !content.ends_with("\n\n") {
+                        content.push('\n');
+                    }
+                    content.push_str(&block.to_string());
+                    content.push_str(if index + 1 < self.blocks.len() {
+                        "\n\n"
+                    } else {
+                        "\n"
+                    });
+                }
+            }
+        }
+
+        write!(f, "{}", content)
     }
 }
@@ -6,9 +6,10 @@ use crate::utils::markdown::MarkdownBlock;
 pub static ORDERED_LIST_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\s*(\d+)\.\s+(.+?)\s*$").unwrap());
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct OrderedList {
     pub items: Vec<String>,
+    pub source: Option<String>,
 }
 
 impl fmt::Display for OrderedList {
@@ -45,7 +46,10 @@ pub fn parse_ordered_list(lines: &[&str], start_index: usize) -> (MarkdownBlock,
     }
 
     (
-        MarkdownBlock::OrderedList(OrderedList { items }),
+        MarkdownBlock::OrderedList(OrderedList {
+            items,
+            source: None,
+        }),
         i - start_index,
     )
 }
@@ -1,8 +1,18 @@
 use std::fmt;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Paragraph {
     pub content: String,
+    pub source: Option<String>,
+}
+
+impl Paragraph {
+    pub fn new(content: String) -> Self {
+        Paragraph {
+            content,
+            source: None,
+        }
+    }
 }
 
 impl fmt::Display for Paragraph {
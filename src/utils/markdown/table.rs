@@ -0,0 +1,141 @@
+use regex::Regex;
+use std::{fmt, sync::LazyLock};
+
+use crate::utils::markdown::MarkdownBlock;
+
+pub static TABLE_ALIGNMENT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*\|?\s*:?-+:?\s*(\|\s*:?-+:?\s*)*\|?\s*$").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub alignments: Vec<Alignment>,
+    pub rows: Vec<Vec<String>>,
+    pub source: Option<String>,
+}
+
+impl Table {
+    pub fn new(header: Vec<String>, alignments: Vec<Alignment>, rows: Vec<Vec<String>>) -> Self {
+        Table {
+            header,
+            alignments,
+            rows,
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.header.len(), self.alignments.len()])
+            .max()
+            .unwrap_or(0);
+
+        // Pad every column to its widest cell, with room for at least `---`
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .chain(std::iter::once(&self.header))
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+
+        let render_row = |row: &Vec<String>| {
+            let cells = (0..columns)
+                .map(|column| {
+                    let cell = row.get(column).map(String::as_str).unwrap_or("");
+                    format!("{:<width$}", cell, width = widths[column])
+                })
+                .collect::<Vec<_>>();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let alignment_row = (0..columns)
+            .map(|column| {
+                let width = widths[column];
+                match self.alignments.get(column).unwrap_or(&Alignment::None) {
+                    Alignment::None => "-".repeat(width),
+                    Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                    Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                    Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut lines = vec![
+            render_row(&self.header),
+            format!("| {} |", alignment_row.join(" | ")),
+        ];
+        lines.extend(self.rows.iter().map(render_row));
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// A table starts with a row containing a pipe followed by an alignment row like `|---|:-:|`
+pub fn is_table_start(lines: &[&str], index: usize) -> bool {
+    lines[index].contains('|')
+        && lines
+            .get(index + 1)
+            .is_some_and(|line| line.contains('-') && TABLE_ALIGNMENT_REGEX.is_match(line))
+}
+
+pub fn parse_table(lines: &[&str], start_index: usize) -> (MarkdownBlock, usize) {
+    let header = split_row(lines[start_index]);
+    let alignments = split_row(lines[start_index + 1])
+        .iter()
+        .map(|cell| parse_alignment(cell))
+        .collect();
+
+    let mut rows = Vec::new();
+    let mut i = start_index + 2;
+
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim().is_empty() || !line.contains('|') {
+            break;
+        }
+        rows.push(split_row(line));
+        i += 1;
+    }
+
+    (
+        MarkdownBlock::Table(Table::new(header, alignments, rows)),
+        i - start_index,
+    )
+}
+
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    line.split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+fn parse_alignment(cell: &str) -> Alignment {
+    match (cell.starts_with(':'), cell.ends_with(':')) {
+        (true, true) => Alignment::Center,
+        (true, false) => Alignment::Left,
+        (false, true) => Alignment::Right,
+        (false, false) => Alignment::None,
+    }
+}
@@ -0,0 +1,23 @@
+use regex::Regex;
+use std::{fmt, sync::LazyLock};
+
+pub static THEMATIC_BREAK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:(?:-[ \t]*){3,}|(?:\*[ \t]*){3,}|(?:_[ \t]*){3,})$").unwrap()
+});
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThematicBreak {
+    pub source: Option<String>,
+}
+
+impl ThematicBreak {
+    pub fn new() -> Self {
+        ThematicBreak { source: None }
+    }
+}
+
+impl fmt::Display for ThematicBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "---")
+    }
+}
@@ -1,25 +1,86 @@
+use chrono::NaiveTime;
 use regex::Regex;
 use std::{fmt, sync::LazyLock};
 
-use crate::utils::markdown::MarkdownBlock;
+use crate::utils::markdown::{MarkdownBlock, checklist::CHECKLIST_REGEX};
 
 pub static UNORDERED_LIST_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^(\s*)[-*+]\s+(.+?)\s*$").unwrap());
 
+/// Time of day at the start of a note, as written by `wl note --time`
+static TIMESTAMP_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{1,2}:\d{2})\s+(.+)$").unwrap());
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnorderedListItem {
     pub content: String,
     pub indentation_level: usize,
 }
 
-#[derive(Debug, Clone)]
+impl UnorderedListItem {
+    /// A top level item for `text`, prefixed with `time` when given (`14:32 Paired on auth bug`)
+    pub fn new(text: &str, time: Option<NaiveTime>) -> Self {
+        let content = match time {
+            Some(time) => format!("{} {}", time.format("%H:%M"), text),
+            None => text.to_string(),
+        };
+        UnorderedListItem {
+            content,
+            indentation_level: 0,
+        }
+    }
+
+    /// The time this item was logged at, if its content starts with one
+    pub fn timestamp(&self) -> Option<NaiveTime> {
+        self.split_timestamp().map(|(time, _)| time)
+    }
+
+    /// The item's content without its leading timestamp
+    pub fn text(&self) -> &str {
+        self.split_timestamp()
+            .map_or(self.content.as_str(), |(_, text)| text)
+    }
+
+    fn split_timestamp(&self) -> Option<(NaiveTime, &str)> {
+        let caps = TIMESTAMP_REGEX.captures(&self.content)?;
+        let time = NaiveTime::parse_from_str(caps.get(1)?.as_str(), "%H:%M").ok()?;
+        Some((time, caps.get(2)?.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct UnorderedList {
     pub items: Vec<UnorderedListItem>,
+    pub source: Option<String>,
 }
 
 impl UnorderedList {
     pub fn new() -> Self {
-        UnorderedList { items: Vec::new() }
+        UnorderedList {
+            items: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Adds a top level item and the items nested under it at the end, or before the first
+    /// top level item logged later than it when it has a timestamp, so back-dated notes
+    /// keep the list in order
+    pub fn push_in_time_order(
+        &mut self,
+        item: UnorderedListItem,
+        children: Vec<UnorderedListItem>,
+    ) {
+        let position = item
+            .timestamp()
+            .and_then(|time| {
+                self.items.iter().position(|other| {
+                    other.indentation_level == 0
+                        && other.timestamp().is_some_and(|other| other > time)
+                })
+            })
+            .unwrap_or(self.items.len());
+        self.items
+            .splice(position..position, std::iter::once(item).chain(children));
     }
 }
 
@@ -50,6 +111,11 @@ pub fn parse_unordered_list(lines: &[&str], start_index: usize) -> (MarkdownBloc
             continue;
         }
 
+        // Tasks start a checklist of their own, even right after a plain bullet
+        if CHECKLIST_REGEX.is_match(line.trim()) {
+            break;
+        }
+
         if let Some(caps) = UNORDERED_LIST_REGEX.captures(line) {
             let whitespace = &caps[1];
             // Convert tabs to equivalent spaces (1 tab = 4 spaces) and count indentation
@@ -67,7 +133,10 @@ pub fn parse_unordered_list(lines: &[&str], start_index: usize) -> (MarkdownBloc
     }
 
     (
-        MarkdownBlock::UnorderedList(UnorderedList { items }),
+        MarkdownBlock::UnorderedList(UnorderedList {
+            items,
+            source: None,
+        }),
         i - start_index,
     )
 }
@@ -1,2 +1,5 @@
+pub mod input;
 pub mod markdown;
+pub mod schedule;
+pub mod template;
 pub mod time;
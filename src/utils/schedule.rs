@@ -0,0 +1,106 @@
+// Which days something applies to, written the way people say it: `monday`, `mon,thu`,
+// `workday`, `weekend`, `1st of month`, `first workday of month` or `2025-12-24`.
+
+use std::{str::FromStr, sync::LazyLock};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use regex::Regex;
+
+static DAY_OF_MONTH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{1,2})(?:st|nd|rd|th)? of (?:the |each |every )?month$").unwrap()
+});
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Daily,
+    /// Monday to Friday
+    Workdays,
+    Weekends,
+    Weekdays(Vec<Weekday>),
+    /// The given day of each month, or its last day in months that are shorter
+    DayOfMonth(u32),
+    FirstWorkdayOfMonth,
+    LastWorkdayOfMonth,
+    Date(NaiveDate),
+}
+
+impl Schedule {
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            Schedule::Daily => true,
+            Schedule::Workdays => is_workday(date),
+            Schedule::Weekends => !is_workday(date),
+            Schedule::Weekdays(weekdays) => weekdays.contains(&date.weekday()),
+            Schedule::DayOfMonth(day) => {
+                date.day() == *day || (date.day() < *day && is_last_day_of_month(date))
+            }
+            Schedule::FirstWorkdayOfMonth => {
+                is_workday(date)
+                    && (1..date.day())
+                        .filter_map(|day| date.with_day(day))
+                        .all(|earlier| !is_workday(earlier))
+            }
+            Schedule::LastWorkdayOfMonth => {
+                is_workday(date)
+                    && date
+                        .iter_days()
+                        .skip(1)
+                        .take_while(|later| later.month() == date.month())
+                        .all(|later| !is_workday(later))
+            }
+            Schedule::Date(wanted) => date == *wanted,
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let normalized = input.trim().to_lowercase();
+        let normalized = normalized.strip_prefix("every ").unwrap_or(&normalized);
+
+        let schedule = match normalized {
+            "day" | "daily" => Schedule::Daily,
+            "weekday" | "weekdays" | "workday" | "workdays" => Schedule::Workdays,
+            "weekend" | "weekends" => Schedule::Weekends,
+            "first workday of month" | "first workday of the month" => {
+                Schedule::FirstWorkdayOfMonth
+            }
+            "last workday of month" | "last workday of the month" => Schedule::LastWorkdayOfMonth,
+            _ => {
+                if let Ok(date) = NaiveDate::parse_from_str(normalized, "%Y-%m-%d") {
+                    Schedule::Date(date)
+                } else if let Some(caps) = DAY_OF_MONTH_REGEX.captures(normalized) {
+                    match caps[1].parse() {
+                        Ok(day @ 1..=31) => Schedule::DayOfMonth(day),
+                        _ => return Err(format!("'{}' is not a day of the month", input.trim())),
+                    }
+                } else {
+                    let weekdays = normalized
+                        .split(',')
+                        .map(|weekday| weekday.trim().parse::<Weekday>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| {
+                            format!(
+                                "Unknown schedule '{}', expected e.g. monday, mon,thu, workday, \
+                                 weekend, 1st of month, first workday of month or 2025-12-24",
+                                input.trim()
+                            )
+                        })?;
+                    Schedule::Weekdays(weekdays)
+                }
+            }
+        };
+        Ok(schedule)
+    }
+}
+
+fn is_workday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt()
+        .is_none_or(|next| next.month() != date.month())
+}
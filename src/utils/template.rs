@@ -0,0 +1,273 @@
+// A small template language for daily note templates.
+//
+//   {{DATE}}                    variables, see `TemplateContext`
+//   {{DATE | %A, %B %d}}        dates formatted with a strftime pattern
+//   {{#if friday}}...{{/if}}    sections kept only on days matching a `Schedule`, with an
+//                               optional {{else}}; `{{#if not weekend}}` negates
+//
+// Tags on a line of their own don't leave a blank line behind.
+
+use std::{collections::BTreeMap, fmt::Write, sync::LazyLock};
+
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
+
+use crate::utils::schedule::Schedule;
+
+static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\{\s*(.*?)\s*\}\}").unwrap());
+
+/// What a template can refer to
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    /// `{{DATE}}`, the day the note is for
+    pub date: NaiveDate,
+    /// `{{YESTERDAY}}` and `{{YESTERDAY_LINK}}`, the day of the previous note if there is one
+    pub previous: Option<NaiveDate>,
+    /// `{{USER}}`
+    pub user: String,
+    /// Variables from `template_variables` in the config
+    pub custom: BTreeMap<String, String>,
+}
+
+impl TemplateContext {
+    pub fn new(date: NaiveDate, previous: Option<NaiveDate>) -> Self {
+        TemplateContext {
+            date,
+            previous,
+            user: std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .unwrap_or_default(),
+            custom: BTreeMap::new(),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Value> {
+        let yesterday = self
+            .previous
+            .unwrap_or_else(|| self.date.pred_opt().unwrap_or(self.date));
+        let value = match name {
+            "DATE" => Value::Date(self.date),
+            "YESTERDAY" => Value::Date(yesterday),
+            "WEEKDAY" => Value::Text(self.date.format("%A").to_string()),
+            "WEEK_NUMBER" => Value::Text(self.date.iso_week().week().to_string()),
+            "YESTERDAY_LINK" => Value::Text(format!("[{0}]({0}.md)", yesterday)),
+            "USER" => Value::Text(self.user.clone()),
+            _ => Value::Text(self.custom.get(name)?.clone()),
+        };
+        Some(value)
+    }
+
+    fn condition(&self, condition: &str) -> Result<bool, String> {
+        let condition = condition.trim().to_lowercase();
+        if let Some(negated) = condition.strip_prefix("not ") {
+            return self.condition(negated).map(|result| !result);
+        }
+
+        condition
+            .parse::<Schedule>()
+            .map(|schedule| schedule.matches(self.date))
+            .map_err(|_| {
+                format!(
+                    "Unknown template condition '{}', expected a day such as friday, mon,thu, \
+                     workday, weekend or first workday of month",
+                    condition
+                )
+            })
+    }
+}
+
+enum Value {
+    Date(NaiveDate),
+    Text(String),
+}
+
+#[derive(Debug)]
+enum Token<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+    If(&'a str),
+    Else,
+    EndIf,
+}
+
+#[derive(Debug)]
+enum Node<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+    If {
+        condition: &'a str,
+        then: Vec<Node<'a>>,
+        otherwise: Vec<Node<'a>>,
+    },
+}
+
+/// Fills in `template` from `context`; unknown variables and malformed tags are errors
+/// rather than being left in the note
+pub fn render_template(template: &str, context: &TemplateContext) -> Result<String, String> {
+    let nodes = parse(tokenize(template))?;
+    let mut output = String::new();
+    render_nodes(&nodes, context, &mut output)?;
+    Ok(output)
+}
+
+fn tokenize(template: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    for caps in TAG_REGEX.captures_iter(template) {
+        let tag = caps.get(0).unwrap();
+        let inner = caps.get(1).map_or("", |inner| inner.as_str());
+        let token = if let Some(condition) = inner.strip_prefix("#if ") {
+            Token::If(condition.trim())
+        } else if inner == "else" {
+            Token::Else
+        } else if inner == "/if" {
+            Token::EndIf
+        } else {
+            Token::Variable(inner)
+        };
+
+        let mut text_end = tag.start();
+        let mut next = tag.end();
+        if !matches!(token, Token::Variable(_)) {
+            // Drop the whole line when the block tag is the only thing on it
+            let line_start = template[..tag.start()]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            let line_end = template[tag.end()..]
+                .find('\n')
+                .map_or(template.len(), |index| tag.end() + index + 1);
+            if line_start >= position
+                && template[line_start..tag.start()].trim().is_empty()
+                && template[tag.end()..line_end].trim().is_empty()
+            {
+                text_end = line_start;
+                next = line_end;
+            }
+        }
+
+        if text_end > position {
+            tokens.push(Token::Text(&template[position..text_end]));
+        }
+        tokens.push(token);
+        position = next;
+    }
+
+    if position < template.len() {
+        tokens.push(Token::Text(&template[position..]));
+    }
+    tokens
+}
+
+/// An `{{#if}}` whose `{{/if}}` hasn't been reached yet
+struct OpenIf<'a> {
+    condition: &'a str,
+    /// Nodes before the `{{#if}}`, to go back to once it's closed
+    outer: Vec<Node<'a>>,
+    /// Nodes before the `{{else}}`, once there has been one
+    then: Option<Vec<Node<'a>>>,
+}
+
+fn parse(tokens: Vec<Token<'_>>) -> Result<Vec<Node<'_>>, String> {
+    let mut stack: Vec<OpenIf> = Vec::new();
+    let mut nodes = Vec::new();
+
+    for token in tokens {
+        let node = match token {
+            Token::Text(text) => Node::Text(text),
+            Token::Variable(name) => Node::Variable(name),
+            Token::If(condition) => {
+                stack.push(OpenIf {
+                    condition,
+                    outer: std::mem::take(&mut nodes),
+                    then: None,
+                });
+                continue;
+            }
+            Token::Else => {
+                let Some(open) = stack.last_mut() else {
+                    return Err("Template has {{else}} without a matching {{#if}}".to_string());
+                };
+                if open.then.is_some() {
+                    return Err("Template has two {{else}} tags in one {{#if}}".to_string());
+                }
+                open.then = Some(std::mem::take(&mut nodes));
+                continue;
+            }
+            Token::EndIf => {
+                let Some(open) = stack.pop() else {
+                    return Err("Template has {{/if}} without a matching {{#if}}".to_string());
+                };
+                let branch = std::mem::replace(&mut nodes, open.outer);
+                let (then, otherwise) = match open.then {
+                    Some(then) => (then, branch),
+                    None => (branch, Vec::new()),
+                };
+                Node::If {
+                    condition: open.condition,
+                    then,
+                    otherwise,
+                }
+            }
+        };
+        nodes.push(node);
+    }
+
+    if let Some(open) = stack.last() {
+        return Err(format!(
+            "Template has {{{{#if {}}}}} without a closing {{{{/if}}}}",
+            open.condition
+        ));
+    }
+    Ok(nodes)
+}
+
+fn render_nodes(
+    nodes: &[Node<'_>],
+    context: &TemplateContext,
+    output: &mut String,
+) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable(expression) => output.push_str(&render_variable(expression, context)?),
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let branch = if context.condition(condition)? {
+                    then
+                } else {
+                    otherwise
+                };
+                render_nodes(branch, context, output)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn render_variable(expression: &str, context: &TemplateContext) -> Result<String, String> {
+    let (name, format) = match expression.split_once('|') {
+        Some((name, format)) => (name.trim(), Some(format.trim())),
+        None => (expression.trim(), None),
+    };
+    let value = context
+        .lookup(name)
+        .ok_or_else(|| format!("Unknown template variable '{{{{{}}}}}'", name))?;
+
+    match (value, format) {
+        (Value::Date(date), format) => {
+            let mut formatted = String::new();
+            write!(formatted, "{}", date.format(format.unwrap_or("%Y-%m-%d")))
+                .map_err(|_| format!("Invalid date format in '{{{{{}}}}}'", expression))?;
+            Ok(formatted)
+        }
+        (Value::Text(text), None) => Ok(text),
+        (Value::Text(_), Some(_)) => Err(format!(
+            "Only dates can be formatted, but '{{{{{}}}}}' is not a date",
+            expression
+        )),
+    }
+}
@@ -1,5 +1,225 @@
-use chrono::Local;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Timelike, Weekday};
 
-pub fn get_today_date() -> String {
-    return Local::now().format("%Y-%m-%d").to_string();
+pub fn get_today_date() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Current local time, to the minute
+pub fn get_current_time() -> NaiveTime {
+    let now = Local::now().time();
+    NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or(now)
+}
+
+/// Monday to Sunday of the week containing `date`
+pub fn week_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let week = date.week(Weekday::Mon);
+    (week.first_day(), week.last_day())
+}
+
+/// First to last day of the month containing `date`
+pub fn month_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let first = date.with_day(1).unwrap_or(date);
+    let next_month = first
+        .checked_add_months(chrono::Months::new(1))
+        .unwrap_or(first);
+    (first, next_month.pred_opt().unwrap_or(date))
+}
+
+/// Formats a duration in hours and minutes, e.g. `1h20m`, `45m` or `2h`
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
+
+/// Parses durations written like `format_duration` does: `1h30m`, `45m` or `2h`
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase();
+    let (hours, minutes) = match input.split_once('h') {
+        Some((hours, minutes)) => (hours, minutes.strip_suffix('m').unwrap_or(minutes)),
+        None => ("0", input.strip_suffix('m')?),
+    };
+    if hours.is_empty() || (minutes.is_empty() && !input.ends_with('h')) {
+        return None;
+    }
+
+    let hours: i64 = hours.parse().ok()?;
+    let minutes: i64 = if minutes.is_empty() {
+        0
+    } else {
+        minutes.parse().ok()?
+    };
+    Duration::try_hours(hours)?.checked_add(&Duration::try_minutes(minutes)?)
+}
+
+/// Parses a time of day such as `09:15`, `9:15`, `14:32`, `9:15am` or `2pm`
+pub fn parse_time_arg(input: &str) -> Result<NaiveTime, String> {
+    let input = input.trim().to_lowercase().replace(' ', "");
+    let (clock, meridiem) = if let Some(clock) = input.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = input.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (input.as_str(), None)
+    };
+
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let hour: Option<u32> = hour.parse().ok();
+    let minute: Option<u32> = (minute.len() <= 2).then(|| minute.parse().ok()).flatten();
+    let hour = match (hour, meridiem) {
+        (Some(hour @ 1..=12), Some(offset)) => Some(hour % 12 + offset),
+        (_, Some(_)) => None,
+        (hour, None) => hour,
+    };
+
+    hour.zip(minute)
+        .and_then(|(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0))
+        .ok_or_else(|| {
+            format!(
+                "Could not understand time '{}' (try 09:15, 14:32 or 2:30pm)",
+                input
+            )
+        })
+}
+
+/// Clap value parser for `--date`, resolving relative forms against today's date
+pub fn parse_date_arg(input: &str) -> Result<NaiveDate, String> {
+    resolve_date(input, get_today_date())
+}
+
+/// Resolves the start of a date range; `7d` and `2w` count back from `today`,
+/// anything else is resolved like `resolve_date`
+pub fn resolve_since(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let trimmed = input.trim();
+    if trimmed.starts_with(|c: char| c.is_ascii_digit())
+        && let Some(offset) = parse_offset(&trimmed.to_lowercase())
+    {
+        return today
+            .checked_sub_signed(offset)
+            .ok_or_else(|| unknown_date_error(trimmed));
+    }
+    resolve_date(input, today)
+}
+
+/// Resolves a user supplied date relative to `today`.
+///
+/// Understands ISO dates (`2025-01-15`), `today`, `yesterday`, `tomorrow`,
+/// offsets (`-3d`, `+2w`, `3 days ago`) and weekdays (`friday`, `last friday`,
+/// `next monday`). A bare weekday means the most recent one, including today.
+pub fn resolve_date(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let input = input.trim().to_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    match input.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        "tomorrow" => return Ok(today + Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some(offset) = parse_offset(&input) {
+        return today
+            .checked_add_signed(offset)
+            .ok_or_else(|| unknown_date_error(&input));
+    }
+
+    let words: Vec<&str> = input.split_whitespace().collect();
+    match words.as_slice() {
+        [day] => {
+            if let Some(weekday) = parse_weekday(day) {
+                return Ok(previous_weekday(today, weekday, true));
+            }
+        }
+        ["last", day] => {
+            if let Some(weekday) = parse_weekday(day) {
+                return Ok(previous_weekday(today, weekday, false));
+            }
+        }
+        ["next", day] => {
+            if let Some(weekday) = parse_weekday(day) {
+                return Ok(next_weekday(today, weekday, false));
+            }
+        }
+        _ => {}
+    }
+
+    Err(unknown_date_error(&input))
+}
+
+fn unknown_date_error(input: &str) -> String {
+    format!(
+        "Could not understand date '{}' (try YYYY-MM-DD, yesterday, -3d, last friday or next monday)",
+        input
+    )
+}
+
+/// Resolves a date that's expected to be in the future, like a due date: the same as
+/// `resolve_date` except that a bare weekday means the next one, including today
+pub fn resolve_future_date(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    match parse_weekday(input.trim().to_lowercase().as_str()) {
+        Some(weekday) => Ok(next_weekday(today, weekday, true)),
+        None => resolve_date(input, today),
+    }
+}
+
+/// Parses signed offsets like `-3d`, `+2w`, `1d` and `3 days ago`
+fn parse_offset(input: &str) -> Option<Duration> {
+    let (sign, rest) = if let Some(rest) = input.strip_suffix(" ago") {
+        (-1, rest.trim())
+    } else if let Some(rest) = input.strip_prefix('-') {
+        (-1, rest)
+    } else if let Some(rest) = input.strip_prefix('+') {
+        (1, rest)
+    } else {
+        (1, input)
+    };
+
+    let split_at = rest.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = rest.split_at(split_at);
+    let amount: i64 = amount.parse().ok()?;
+
+    let days = match unit.trim() {
+        "d" | "day" | "days" => amount,
+        "w" | "week" | "weeks" => amount.checked_mul(7)?,
+        _ => return None,
+    };
+
+    Duration::try_days(sign * days)
+}
+
+fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn previous_weekday(from: NaiveDate, weekday: Weekday, include_today: bool) -> NaiveDate {
+    let mut days_back =
+        (from.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    if days_back == 0 && !include_today {
+        days_back = 7;
+    }
+    from - Duration::days(days_back as i64)
+}
+
+fn next_weekday(from: NaiveDate, weekday: Weekday, include_today: bool) -> NaiveDate {
+    let mut days_ahead =
+        (weekday.num_days_from_monday() + 7 - from.weekday().num_days_from_monday()) % 7;
+    if days_ahead == 0 && !include_today {
+        days_ahead = 7;
+    }
+    from + Duration::days(days_ahead as i64)
 }
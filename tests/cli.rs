@@ -1,12 +1,13 @@
 use std::fs;
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 use tempfile::TempDir;
 
 fn setup_test_env() -> TempDir {
     let temp_dir = TempDir::new().unwrap();
 
     // Create the template file that the CLI expects
-    let template_dir = temp_dir.path().join("templates");
+    let template_dir = temp_dir.path().join(".worklog").join("templates");
     fs::create_dir_all(&template_dir).unwrap();
 
     let template_content = r#"# {{DATE}}
@@ -32,13 +33,49 @@ fn setup_test_env() -> TempDir {
 
 fn run_wl_command(args: &[&str], home_dir: &std::path::Path) -> std::process::Output {
     Command::new("cargo")
-        .args(&["run", "--bin", "wl", "--"])
+        .args(["run", "--bin", "wl", "--"])
         .args(args)
         .env("HOME", home_dir)
         .output()
         .expect("Failed to execute CLI command")
 }
 
+fn run_wl_command_with_stdin(
+    args: &[&str],
+    home_dir: &std::path::Path,
+    stdin: &str,
+) -> std::process::Output {
+    let mut child = Command::new("cargo")
+        .args(["run", "--bin", "wl", "--"])
+        .args(args)
+        .env("HOME", home_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute CLI command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn write_daily_note(home_dir: &std::path::Path, content: &str) -> std::path::PathBuf {
+    let daily_note_path = get_daily_note_path(home_dir);
+    fs::create_dir_all(daily_note_path.parent().unwrap()).unwrap();
+    fs::write(&daily_note_path, content).unwrap();
+    daily_note_path
+}
+
+fn write_config(home_dir: &std::path::Path, content: &str) {
+    let config_dir = home_dir.join(".worklog");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join("config.yaml"), content).unwrap();
+}
+
 fn get_daily_note_path(home_dir: &std::path::Path) -> std::path::PathBuf {
     let today = chrono::Local::now().format("%Y-%m-%d").to_string();
     home_dir
@@ -106,6 +143,457 @@ fn test_wl_open_creates_daily_note() {
     assert!(content.contains(&format!("# {}", today)));
 }
 
+#[test]
+fn test_wl_open_writes_default_template_to_worklog_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    write_config(temp_dir.path(), "editor_command: echo\n");
+
+    let output = run_wl_command(&["open", "--date", "2025-01-15"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let template = temp_dir
+        .path()
+        .join(".worklog")
+        .join("templates")
+        .join("daily.md");
+    assert!(
+        fs::read_to_string(template)
+            .unwrap()
+            .starts_with("# {{DATE}}\n")
+    );
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-15.md"),
+    )
+    .unwrap();
+    assert!(content.starts_with("# 2025-01-15\n\n## Tasks\n"));
+}
+
+#[test]
+fn test_wl_open_with_named_template_keeps_open_tasks() {
+    let temp_dir = setup_test_env();
+    write_config(temp_dir.path(), "editor_command: echo\n");
+    fs::write(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("templates")
+            .join("oncall.md"),
+        "# On call {{DATE}}\n\n## Pages\n\n## Support\n\n## Notes\n",
+    )
+    .unwrap();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n- [x] Close ticket\n",
+    );
+
+    let output = run_wl_command(
+        &["open", "--template", "oncall", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-15.md"),
+    )
+    .unwrap();
+    assert_eq!(
+        content,
+        "# On call 2025-01-15\n\n## Pages\n\n## Support\n\n- [ ] Reply to ticket <!-- since:2025-01-14 -->\n\n## Notes\n"
+    );
+
+    let output = run_wl_command(
+        &["open", "--template", "missing", "--date", "2025-01-16"],
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Template 'missing' not found"));
+}
+
+#[test]
+fn test_wl_unknown_template_variable_is_an_error() {
+    let temp_dir = setup_test_env();
+    fs::write(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("templates")
+            .join("daily.md"),
+        "# {{DATE}}\n\nOwner: {{OWNER}}\n",
+    )
+    .unwrap();
+
+    let output = run_wl_command(
+        &["task", "Write docs", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("Unknown template variable '{{OWNER}}' in template 'daily'")
+    );
+    assert!(
+        !temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-15.md")
+            .exists()
+    );
+
+    write_config(temp_dir.path(), "template_variables:\n  OWNER: sam\n");
+    let output = run_wl_command(
+        &["task", "Write docs", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-15.md"),
+    )
+    .unwrap();
+    assert!(content.starts_with("# 2025-01-15\n\nOwner: sam\n"));
+}
+
+#[test]
+fn test_wl_template_rules_pick_the_template_for_the_day() {
+    let temp_dir = setup_test_env();
+    write_config(
+        temp_dir.path(),
+        "template_rules:\n  - { when: monday, template: planning }\n  - { when: 2025-06-06, template: missing }\n",
+    );
+    fs::write(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("templates")
+            .join("planning.md"),
+        "# {{DATE}}\n\n## Weekly planning\n\n## Tasks\n\n### Intake\n\n## Notes\n",
+    )
+    .unwrap();
+    // 2025-05-30 is a Friday and 2025-06-02 the Monday after
+    write_note_for(
+        temp_dir.path(),
+        "2025-05-30",
+        "# 2025-05-30\n\n## Tasks\n\n### Intake\n\n- [ ] Plan sprint\n- [x] Ship it\n",
+    );
+
+    let output = run_wl_command(
+        &["note", "Kickoff", "--date", "2025-06-02"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let notes_dir = temp_dir.path().join(".worklog").join("daily_notes");
+    assert_eq!(
+        fs::read_to_string(notes_dir.join("2025-06-02.md")).unwrap(),
+        "# 2025-06-02\n\n## Weekly planning\n\n## Tasks\n\n### Intake\n\n- [ ] Plan sprint <!-- since:2025-05-30 -->\n\n## Notes\n\n- Kickoff\n"
+    );
+
+    // Tuesday has no rule, so it carries over from Monday as usual
+    let output = run_wl_command(
+        &["note", "Standup", "--date", "2025-06-03"],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    assert!(
+        fs::read_to_string(notes_dir.join("2025-06-03.md"))
+            .unwrap()
+            .starts_with("# 2025-06-03\n\n## Weekly planning\n")
+    );
+
+    let output = run_wl_command(&["note", "Friday", "--date", "2025-06-06"], temp_dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Template 'missing' not found"));
+}
+
+#[test]
+fn test_wl_merge_into_template_keeps_tasks_and_template_changes() {
+    let temp_dir = setup_test_env();
+    write_config(temp_dir.path(), "merge_into_template: true\n");
+    fs::write(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("templates")
+            .join("daily.md"),
+        "# {{DATE}}\n\n## Tasks\n\n### Priority\n\n### Support\n\n## Blockers\n\n## Notes\n",
+    )
+    .unwrap();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n- [x] Close ticket\n\n### Hiring\n\n- [/] Review CVs\n  - [ ] Shortlist\n\n## Notes\n\n- Old note\n",
+    );
+
+    let output = run_wl_command(&["task", "New", "--date", "2025-01-15"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "Warning: template 'daily' has no 'Hiring' section, so it was added for tasks carried over from 2025-01-14"
+    ));
+
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-15.md"),
+    )
+    .unwrap();
+    assert!(content.starts_with(
+        "# 2025-01-15\n\n## Tasks\n\n### Priority\n\n### Support\n\n- [ ] Reply to ticket <!-- since:2025-01-14 -->\n\n### Hiring\n\n- [/] Review CVs <!-- since:2025-01-14 -->\n  - [ ] Shortlist <!-- since:2025-01-14 -->\n\n"
+    ));
+    assert!(content.contains("## Blockers\n"));
+    assert!(!content.contains("Close ticket"));
+    assert!(!content.contains("Old note"));
+}
+
+#[test]
+fn test_wl_merge_into_template_keeps_same_text_in_different_sections() {
+    let temp_dir = setup_test_env();
+    write_config(
+        temp_dir.path(),
+        "editor_command: echo\nmerge_into_template: true\n",
+    );
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n## Tasks\n\n### Support\n\n- [ ] Write docs\n\n### Engineering\n\n- [ ] Write docs\n",
+    );
+
+    let output = run_wl_command(&["open", "--date", "2025-01-15"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-15.md"),
+    )
+    .unwrap();
+    assert!(content.contains("### Support\n\n- [ ] Write docs <!-- since:2025-01-14 -->\n"));
+    assert!(content.contains("### Engineering\n\n- [ ] Write docs <!-- since:2025-01-14 -->\n"));
+}
+
+#[test]
+fn test_wl_recurring_tasks_are_added_on_their_days() {
+    let temp_dir = setup_test_env();
+    write_config(
+        temp_dir.path(),
+        "recurring:\n  - { task: \"Review on-call dashboard\", every: weekday, section: Support }\n  - { task: Send invoices, every: 1st of month }\n  - { task: Water plants, every: \"mon,thu\" }\n",
+    );
+    // 2025-05-30 is a Friday, 2025-05-31 a Saturday and 2025-06-02 a Monday
+    write_note_for(
+        temp_dir.path(),
+        "2025-05-30",
+        "# 2025-05-30\n\n## Tasks\n\n### Support\n\n- [ ] Review on-call dashboard\n",
+    );
+
+    let notes_dir = temp_dir.path().join(".worklog").join("daily_notes");
+    let output = run_wl_command(&["note", "Quiet", "--date", "2025-05-31"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let saturday = fs::read_to_string(notes_dir.join("2025-05-31.md")).unwrap();
+    assert!(!saturday.contains("Send invoices"));
+    assert!(!saturday.contains("Water plants"));
+
+    let output = run_wl_command(&["note", "Busy", "--date", "2025-06-02"], temp_dir.path());
+    assert!(output.status.success());
+    let monday = fs::read_to_string(notes_dir.join("2025-06-02.md")).unwrap();
+    // Carried over from Friday, so not added a second time
+    assert_eq!(monday.matches("Review on-call dashboard").count(), 1);
+    assert!(monday.contains("### Intake\n\n- [ ] Water plants\n"));
+    assert!(!monday.contains("Send invoices"));
+
+    let output = run_wl_command(
+        &["--fresh", "note", "New month", "--date", "2025-07-01"],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    let july = fs::read_to_string(notes_dir.join("2025-07-01.md")).unwrap();
+    assert!(july.contains("### Support\n\n- [ ] Review on-call dashboard\n"));
+    assert!(july.contains("### Intake\n\n- [ ] Send invoices\n"));
+}
+
+#[test]
+fn test_wl_task_due_date() {
+    let temp_dir = setup_test_env();
+
+    // 2025-01-15 is a Wednesday, so friday is two days later
+    let output = run_wl_command(
+        &[
+            "task",
+            "Send report",
+            "--due",
+            "friday",
+            "--date",
+            "2025-01-15",
+        ],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-15.md"),
+    )
+    .unwrap();
+    assert!(content.contains("### Intake\n\n- [ ] Send report 📅 2025-01-17\n"));
+
+    let output = run_wl_command(
+        &["task", "Later", "--due", "someday", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_wl_task_on_a_later_day_waits_for_its_note() {
+    let temp_dir = setup_test_env();
+    let notes_dir = temp_dir.path().join(".worklog").join("daily_notes");
+    let scheduled_path = temp_dir.path().join(".worklog").join("scheduled.md");
+
+    let output = run_wl_command(
+        &[
+            "task",
+            "Renew certificate",
+            "--on",
+            "2025-01-20",
+            "--section",
+            "Engineering",
+            "--date",
+            "2025-01-15",
+        ],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains("Scheduled for 2025-01-20: Renew certificate")
+    );
+    let output = run_wl_command(
+        &["task", "Plan Q2", "--on", "+2w", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    assert!(!notes_dir.join("2025-01-15.md").exists());
+    let queue = fs::read_to_string(&scheduled_path).unwrap();
+    assert!(queue.contains("- [ ] Renew certificate ⏳ 2025-01-20\n"));
+    assert!(queue.contains("- [ ] Plan Q2 ⏳ 2025-01-29\n"));
+
+    // Creating a note on or after the day pulls the task in and out of the queue
+    let output = run_wl_command(&["note", "Back", "--date", "2025-01-21"], temp_dir.path());
+    assert!(output.status.success());
+    let content = fs::read_to_string(notes_dir.join("2025-01-21.md")).unwrap();
+    assert!(content.contains("### Engineering\n\n- [ ] Renew certificate\n"));
+    assert!(!content.contains("Plan Q2"));
+    let queue = fs::read_to_string(&scheduled_path).unwrap();
+    assert!(!queue.contains("Renew certificate"));
+    assert!(queue.contains("- [ ] Plan Q2 ⏳ 2025-01-29\n"));
+
+    // Days that already have a note get the task straight away
+    let output = run_wl_command(
+        &[
+            "task",
+            "Follow up",
+            "--on",
+            "2025-01-21",
+            "--date",
+            "2025-01-15",
+        ],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    let content = fs::read_to_string(notes_dir.join("2025-01-21.md")).unwrap();
+    assert!(content.contains("- [ ] Follow up\n"));
+}
+
+#[test]
+fn test_wl_tasks_overdue() {
+    let temp_dir = setup_test_env();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-15",
+        "# 2025-01-15\n\n- [ ] Send report 📅 2025-01-10\n- [/] Review PR due:2025-01-14\n- [ ] Plan offsite 📅 2025-01-20\n- [x] Renew domain 📅 2025-01-01\n- [ ] No date\n",
+    );
+
+    let output = run_wl_command(
+        &[
+            "tasks",
+            "--overdue",
+            "--date",
+            "2025-01-15",
+            "--color",
+            "never",
+        ],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(" Send report 📅 2025-01-10 (5d overdue)"));
+    assert!(stdout.contains(" Review PR due:2025-01-14 (1d overdue)"));
+    assert!(!stdout.contains("Plan offsite"));
+    assert!(!stdout.contains("Renew domain"));
+    assert!(!stdout.contains("No date"));
+
+    let output = run_wl_command(
+        &["tasks", "--overdue", "--date", "2025-01-15", "--json"],
+        temp_dir.path(),
+    );
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tasks = tasks.as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0]["due"], "2025-01-10");
+    assert_eq!(tasks[0]["overdue_days"], 5);
+}
+
 #[test]
 fn test_wl_last_opens_most_recent_note() {
     let temp_dir = setup_test_env();
@@ -145,9 +633,10 @@ fn test_wl_note_empty_description() {
     let temp_dir = setup_test_env();
     let output = run_wl_command(&["note", ""], temp_dir.path());
 
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Cannot add a note without a <description>"));
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Cannot add a note without a <description>"));
+    assert!(!get_daily_note_path(temp_dir.path()).exists());
 }
 
 #[test]
@@ -209,7 +698,7 @@ fn test_wl_multiple_tasks_and_notes() {
 #[test]
 fn test_wl_help_flag() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "wl", "--", "--help"])
+        .args(["run", "--bin", "wl", "--", "--help"])
         .output()
         .expect("Failed to execute CLI command");
 
@@ -226,7 +715,7 @@ fn test_wl_help_flag() {
 #[test]
 fn test_wl_version_flag() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "wl", "--", "--version"])
+        .args(["run", "--bin", "wl", "--", "--version"])
         .output()
         .expect("Failed to execute CLI command");
 
@@ -238,7 +727,7 @@ fn test_wl_version_flag() {
 #[test]
 fn test_wl_unknown_command() {
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "wl", "--", "unknown"])
+        .args(["run", "--bin", "wl", "--", "unknown"])
         .output()
         .expect("Failed to execute CLI command");
 
@@ -301,3 +790,1471 @@ fn test_task_goes_to_intake_section_not_notes() {
         "Task should appear before ## Notes section"
     );
 }
+
+#[test]
+fn test_wl_task_with_date_writes_to_that_note() {
+    let temp_dir = setup_test_env();
+    let output = run_wl_command(
+        &["task", "Backfilled task", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let note_path = temp_dir
+        .path()
+        .join(".worklog")
+        .join("daily_notes")
+        .join("2025-01-15.md");
+    let content = fs::read_to_string(&note_path).unwrap();
+    assert!(content.contains("# 2025-01-15"));
+    assert!(content.contains("- [ ] Backfilled task"));
+    assert!(!get_daily_note_path(temp_dir.path()).exists());
+}
+
+#[test]
+fn test_wl_note_with_relative_date() {
+    let temp_dir = setup_test_env();
+    let output = run_wl_command(
+        &["note", "Late night fix", "--date", "yesterday"],
+        temp_dir.path(),
+    );
+
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let yesterday = (chrono::Local::now() - chrono::Duration::days(1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let note_path = temp_dir
+        .path()
+        .join(".worklog")
+        .join("daily_notes")
+        .join(format!("{}.md", yesterday));
+    let content = fs::read_to_string(&note_path).unwrap();
+    assert!(content.contains("- Late night fix"));
+}
+
+#[test]
+fn test_wl_invalid_date_fails() {
+    let temp_dir = setup_test_env();
+    let output = run_wl_command(&["task", "Some task", "--date", "someday"], temp_dir.path());
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Could not understand date"));
+}
+
+#[test]
+fn test_wl_task_preserves_unrelated_content() {
+    let temp_dir = setup_test_env();
+
+    let original = r#"# Today
+
+## Tasks
+
+### Intake
+
+- [ ] Existing task
+
+<!-- Where quick-add tasks are added for later triaging -->
+
+## Notes
+
+```sh
+cargo   test
+```
+"#;
+    let daily_note_path = write_daily_note(temp_dir.path(), original);
+
+    let output = run_wl_command(&["task", "New task"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        original.replace(
+            "- [ ] Existing task\n",
+            "- [ ] Existing task\n- [ ] New task\n"
+        )
+    );
+}
+
+#[test]
+fn test_wl_task_with_parent_adds_subtask() {
+    let temp_dir = setup_test_env();
+    run_wl_command(&["task", "Ship auth feature"], temp_dir.path());
+    let output = run_wl_command(
+        &["task", "Write migration", "--parent", "auth feature"],
+        temp_dir.path(),
+    );
+
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(get_daily_note_path(temp_dir.path())).unwrap();
+    assert!(content.contains("- [ ] Ship auth feature\n  - [ ] Write migration"));
+}
+
+#[test]
+fn test_wl_tasks_after_a_plain_bullet_are_still_tasks() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n- [ ] Parent\n  - context link\n- [ ] Other\n- [x] Finished\n",
+    );
+
+    let output = run_wl_command(&["tasks", "--color", "never"], temp_dir.path());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(" Other"));
+    assert!(stdout.contains(" Finished"));
+
+    let output = run_wl_command(&["done", "Other"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = run_wl_command(&["task", "New one", "--section", "Tasks"], temp_dir.path());
+    assert!(output.status.success());
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(
+        content.contains(
+            "- [ ] Parent\n  - context link\n- [x] Other\n- [x] Finished\n- [ ] New one\n"
+        )
+    );
+}
+
+#[test]
+fn test_wl_done_tells_tasks_with_the_same_text_apart_by_id() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n### Support\n\n- [ ] Review PR\n\n### Engineering\n\n- [ ] Review PR\n",
+    );
+
+    let output = run_wl_command(&["tasks", "--json"], temp_dir.path());
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tasks = tasks.as_array().unwrap();
+    let first = tasks[0]["id"].as_str().unwrap();
+    let second = tasks[1]["id"].as_str().unwrap();
+    assert_eq!(second, format!("{}-2", first));
+
+    let output = run_wl_command(&["done", second], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("### Support\n\n- [ ] Review PR\n"));
+    assert!(content.contains("### Engineering\n\n- [x] Review PR\n"));
+}
+
+#[test]
+fn test_wl_task_with_unknown_parent_fails() {
+    let temp_dir = setup_test_env();
+    let output = run_wl_command(
+        &["task", "Orphan", "--parent", "does not exist"],
+        temp_dir.path(),
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No task matches 'does not exist'"));
+}
+
+#[test]
+fn test_wl_done_and_undo_toggle_task() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n\n### Intake\n\n- [ ] Fix login bug\n",
+    );
+
+    let output = run_wl_command(&["done", "login"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Completed: Fix login bug"));
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("- [x] Fix login bug"));
+    assert!(content.contains("- [ ] Reply to ticket"));
+
+    let output = run_wl_command(&["undo", "Fix login bug"], temp_dir.path());
+    assert!(output.status.success());
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("- [ ] Fix login bug"));
+}
+
+#[test]
+fn test_wl_done_ignores_annotations_when_matching() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n- [ ] Write report 📅 2025-01-20\n- [ ] Plan 2025 roadmap\n",
+    );
+
+    let output = run_wl_command(&["done", "2025"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Completed: Plan 2025 roadmap"));
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("- [ ] Write report 📅 2025-01-20\n- [x] Plan 2025 roadmap\n"));
+}
+
+#[test]
+fn test_wl_done_ambiguous_match_uses_pick_list() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n### Engineering\n\n- [ ] Review PR 12\n\n### Support\n\n- [ ] Review escalations\n",
+    );
+
+    let output = run_wl_command_with_stdin(&["done", "review"], temp_dir.path(), "2\n");
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("  1. [ ] "));
+    assert!(stderr.contains("Review PR 12 (Engineering)"));
+    assert!(stderr.contains("  2. [ ] "));
+    assert!(stderr.contains("Review escalations (Support)"));
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("- [ ] Review PR 12"));
+    assert!(content.contains("- [x] Review escalations"));
+}
+
+#[test]
+fn test_wl_done_ambiguous_match_without_selection_fails() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n- [ ] Review PR 12\n- [ ] Review escalations\n",
+    );
+
+    let output = run_wl_command(&["done", "review"], temp_dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No task selected for 'review'"));
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(!content.contains("[x]"));
+}
+
+#[test]
+fn test_wl_done_prefers_open_tasks() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n- [x] Deploy staging\n- [ ] Deploy production\n",
+    );
+
+    let output = run_wl_command(&["done", "deploy"], temp_dir.path());
+    assert!(output.status.success());
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("- [x] Deploy production"));
+}
+
+fn write_note_for(home_dir: &std::path::Path, date: &str, content: &str) {
+    let daily_notes_dir = home_dir.join(".worklog").join("daily_notes");
+    fs::create_dir_all(&daily_notes_dir).unwrap();
+    fs::write(daily_notes_dir.join(format!("{}.md", date)), content).unwrap();
+}
+
+#[test]
+fn test_wl_carried_tasks_keep_their_origin_and_list_when_stale() {
+    let temp_dir = setup_test_env();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-08",
+        "# 2025-01-08\n\n- [ ] Write docs <!-- since:2025-01-02 -->\n- [ ] Review PR\n- [x] Ship it\n",
+    );
+
+    let output = run_wl_command(
+        &["task", "Fresh task", "--date", "2025-01-09"],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-09.md"),
+    )
+    .unwrap();
+    assert!(content.contains("- [ ] Write docs <!-- since:2025-01-02 -->\n"));
+    assert!(content.contains("- [ ] Review PR <!-- since:2025-01-08 -->\n"));
+    assert!(content.contains("- [ ] Fresh task\n"));
+    assert!(!content.contains("Ship it"));
+
+    let output = run_wl_command(
+        &[
+            "tasks",
+            "--stale",
+            "5d",
+            "--date",
+            "2025-01-09",
+            "--color",
+            "never",
+        ],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(" Write docs (7d, since 2025-01-02)"));
+    assert!(!stdout.contains("Review PR"));
+    assert!(!stdout.contains("Fresh task"));
+
+    let output = run_wl_command(
+        &["tasks", "--stale", "1d", "--date", "2025-01-09", "--json"],
+        temp_dir.path(),
+    );
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tasks = tasks.as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[1]["content"], "Review PR");
+    assert_eq!(tasks[1]["since"], "2025-01-08");
+    assert_eq!(tasks[1]["age_days"], 1);
+}
+
+#[test]
+fn test_wl_backfilled_note_carries_from_the_note_before_it() {
+    let temp_dir = setup_test_env();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-10",
+        "# 2025-01-10\n\n- [ ] Older task\n",
+    );
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-15",
+        "# 2025-01-15\n\n- [ ] Newer task\n",
+    );
+    write_note_for(temp_dir.path(), "2025-01-11.backup", "- [ ] Stray task\n");
+
+    let output = run_wl_command(
+        &["task", "Backfill", "--date", "2025-01-12"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-12.md"),
+    )
+    .unwrap();
+    assert!(content.starts_with("# 2025-01-12\n"));
+    assert!(content.contains("- [ ] Older task <!-- since:2025-01-10 -->"));
+    assert!(!content.contains("Newer task"));
+    assert!(!content.contains("Stray task"));
+}
+
+#[test]
+fn test_wl_carry_over_migrate_marks_previous_tasks() {
+    let temp_dir = setup_test_env();
+    write_config(temp_dir.path(), "carry_over: migrate\n");
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n- [ ] Write docs\n- [x] Ship it\n",
+    );
+
+    let output = run_wl_command(&["task", "New", "--date", "2025-01-15"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let notes_dir = temp_dir.path().join(".worklog").join("daily_notes");
+    let previous = fs::read_to_string(notes_dir.join("2025-01-14.md")).unwrap();
+    assert_eq!(
+        previous,
+        "# 2025-01-14\n\n- [>] Write docs\n- [x] Ship it\n"
+    );
+    let today = fs::read_to_string(notes_dir.join("2025-01-15.md")).unwrap();
+    assert!(today.contains("- [ ] Write docs <!-- since:2025-01-14 -->\n"));
+}
+
+#[test]
+fn test_wl_carry_over_migrate_into_template_only_marks_carried_tasks() {
+    let temp_dir = setup_test_env();
+    write_config(
+        temp_dir.path(),
+        "carry_over: migrate\nmerge_into_template: true\nrecurring:\n  - { task: Check queue, every: day, section: Support }\n",
+    );
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n## Tasks\n\n### Support\n\n- [ ] Write docs\n- [x] Close ticket\n\n### Engineering\n\n- [ ] Write docs\n  - [ ] Outline\n",
+    );
+
+    let output = run_wl_command(&["task", "New", "--date", "2025-01-15"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let notes_dir = temp_dir.path().join(".worklog").join("daily_notes");
+    let previous = fs::read_to_string(notes_dir.join("2025-01-14.md")).unwrap();
+    assert_eq!(
+        previous,
+        "# 2025-01-14\n\n## Tasks\n\n### Support\n\n- [>] Write docs\n- [x] Close ticket\n\n### Engineering\n\n- [>] Write docs\n  - [>] Outline\n"
+    );
+    let today = fs::read_to_string(notes_dir.join("2025-01-15.md")).unwrap();
+    assert!(today.contains(
+        "### Support\n\n- [ ] Write docs <!-- since:2025-01-14 -->\n- [ ] Check queue\n"
+    ));
+    assert!(today.contains(
+        "### Engineering\n\n- [ ] Write docs <!-- since:2025-01-14 -->\n  - [ ] Outline <!-- since:2025-01-14 -->\n"
+    ));
+}
+
+#[test]
+fn test_wl_migrate_moves_open_tasks_without_duplicates() {
+    let temp_dir = setup_test_env();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-10",
+        "# 2025-01-10\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n- [x] Close ticket\n\n### Hiring\n\n- [ ] Review CVs\n",
+    );
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-15",
+        "# 2025-01-15\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n\n## Notes\n",
+    );
+
+    let output = run_wl_command(
+        &["migrate", "--from", "2025-01-10", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Migrated 2 tasks from 2025-01-10 to 2025-01-15\nAdded missing section: Hiring\n"
+    );
+
+    let notes_dir = temp_dir.path().join(".worklog").join("daily_notes");
+    let previous = fs::read_to_string(notes_dir.join("2025-01-10.md")).unwrap();
+    assert!(previous.contains("- [>] Reply to ticket\n- [x] Close ticket\n"));
+    assert!(previous.contains("- [>] Review CVs\n"));
+    let today = fs::read_to_string(notes_dir.join("2025-01-15.md")).unwrap();
+    assert_eq!(today.matches("Reply to ticket").count(), 1);
+    assert!(
+        today.contains("### Hiring\n\n- [ ] Review CVs <!-- since:2025-01-10 -->\n\n## Notes\n")
+    );
+
+    let output = run_wl_command(
+        &["migrate", "--from", "2025-01-10", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "No open tasks to migrate from 2025-01-10\n"
+    );
+}
+
+#[test]
+fn test_wl_migrate_from_yesterday_in_copy_mode() {
+    let temp_dir = setup_test_env();
+    let yesterday = (chrono::Local::now().date_naive() - chrono::Duration::days(1)).to_string();
+    write_note_for(
+        temp_dir.path(),
+        &yesterday,
+        "# Yesterday\n\n## Tasks\n\n### Support\n\n- [ ] Write docs\n- [x] Ship it\n",
+    );
+
+    // Today's note starts as a copy of yesterday's open tasks
+    let output = run_wl_command(&["task", "New"], temp_dir.path());
+    assert!(output.status.success());
+    let output = run_wl_command(&["migrate", "--from", "yesterday"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("Migrated 1 task from {} to ", yesterday)
+            + &chrono::Local::now().date_naive().to_string()
+            + "\n"
+    );
+
+    let previous = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join(format!("{}.md", yesterday)),
+    )
+    .unwrap();
+    assert!(previous.contains("- [>] Write docs\n- [x] Ship it\n"));
+    let today = fs::read_to_string(get_daily_note_path(temp_dir.path())).unwrap();
+    assert_eq!(today.matches("Write docs").count(), 1);
+}
+
+#[test]
+fn test_wl_migrate_into_a_new_note_doesnt_copy_first() {
+    let temp_dir = setup_test_env();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n## Tasks\n\n### Support\n\n- [ ] Write docs\n- [x] Ship it\n",
+    );
+
+    let output = run_wl_command(
+        &["--date", "2025-01-15", "migrate", "--from", "2025-01-14"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Migrated 1 task from 2025-01-14 to 2025-01-15\n"
+    );
+    let notes_dir = temp_dir.path().join(".worklog").join("daily_notes");
+    let previous = fs::read_to_string(notes_dir.join("2025-01-14.md")).unwrap();
+    assert!(previous.contains("- [>] Write docs\n- [x] Ship it\n"));
+    let today = fs::read_to_string(notes_dir.join("2025-01-15.md")).unwrap();
+    assert_eq!(today.matches("Write docs").count(), 1);
+    assert!(today.contains("### Support\n\n- [ ] Write docs <!-- since:2025-01-14 -->\n"));
+}
+
+#[test]
+fn test_wl_tasks_lists_tasks_grouped_by_section() {
+    let temp_dir = setup_test_env();
+    write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n\n### Engineering\n\n- [/] Fix login bug\n  - [x] Write test\n\n## Notes\n\n- Not a task\n",
+    );
+
+    let output = run_wl_command(&["tasks", "--color", "never"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[1], "Support");
+    assert!(lines[2].starts_with("  [ ] ") && lines[2].ends_with(" Reply to ticket"));
+    assert_eq!(lines[3], "Engineering");
+    assert!(lines[4].starts_with("  [/] ") && lines[4].ends_with(" Fix login bug"));
+    assert!(lines[5].starts_with("    [x] ") && lines[5].ends_with(" Write test"));
+    assert!(!stdout.contains("Not a task"));
+    assert!(!stdout.contains("\x1b["));
+}
+
+#[test]
+fn test_wl_tasks_filters_and_json() {
+    let temp_dir = setup_test_env();
+    write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n### Support\n\n- [ ] Reply to ticket\n- [x] Close ticket\n\n### Engineering\n\n- [ ] Fix login bug\n",
+    );
+
+    let output = run_wl_command(
+        &["tasks", "--section", "support", "--open", "--json"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tasks = tasks.as_array().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["content"], "Reply to ticket");
+    assert_eq!(tasks[0]["section"], "Support");
+    assert_eq!(tasks[0]["state"], "todo");
+    assert_eq!(tasks[0]["id"].as_str().unwrap().len(), 5);
+}
+
+#[test]
+fn test_wl_tasks_since_spans_multiple_notes() {
+    let temp_dir = setup_test_env();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-10",
+        "# 2025-01-10\n\n- [x] Old task\n",
+    );
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n- [x] Recent task\n",
+    );
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-15",
+        "# 2025-01-15\n\n- [ ] Today task\n",
+    );
+
+    let output = run_wl_command(
+        &[
+            "tasks",
+            "--since",
+            "3d",
+            "--date",
+            "2025-01-15",
+            "--done",
+            "--color",
+            "never",
+        ],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2025-01-14"));
+    assert!(stdout.contains("Recent task"));
+    assert!(!stdout.contains("Old task"));
+    assert!(!stdout.contains("Today task"));
+}
+
+#[test]
+fn test_wl_done_accepts_task_id() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n- [ ] Review PR 12\n- [ ] Review escalations\n",
+    );
+
+    let output = run_wl_command(&["tasks", "--json"], temp_dir.path());
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = tasks[1]["id"].as_str().unwrap().to_string();
+
+    let output = run_wl_command(&["done", &id], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("- [x] Review escalations"));
+    assert!(content.contains("- [ ] Review PR 12"));
+}
+
+const SECTIONED_NOTE: &str = "# Today\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n\n### Intake\n\n## Notes\n\n- A note\n";
+
+#[test]
+fn test_wl_task_section_flag() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(temp_dir.path(), SECTIONED_NOTE);
+
+    let output = run_wl_command(
+        &["task", "Escalate outage", "--section", "support"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        SECTIONED_NOTE.replace(
+            "- [ ] Reply to ticket\n",
+            "- [ ] Reply to ticket\n- [ ] Escalate outage\n"
+        )
+    );
+}
+
+#[test]
+fn test_wl_task_creates_missing_section_under_tasks() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(temp_dir.path(), SECTIONED_NOTE);
+
+    let output = run_wl_command(
+        &["task", "Draft contract", "--section", "Project Management"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        SECTIONED_NOTE.replace(
+            "### Intake\n\n",
+            "### Intake\n\n### Project Management\n\n- [ ] Draft contract\n\n"
+        )
+    );
+}
+
+#[test]
+fn test_wl_task_uses_configured_default_section_and_shorthands() {
+    let temp_dir = setup_test_env();
+    write_config(
+        temp_dir.path(),
+        "editor_command: nvim\ndefault_task_section: Support\nsection_shorthands:\n  inbox: Intake\n",
+    );
+    let daily_note_path = write_daily_note(temp_dir.path(), SECTIONED_NOTE);
+
+    let output = run_wl_command(&["task", "Check queue"], temp_dir.path());
+    assert!(output.status.success());
+    let output = run_wl_command(&["task", "Triage later", "--inbox"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        SECTIONED_NOTE
+            .replace(
+                "- [ ] Reply to ticket\n",
+                "- [ ] Reply to ticket\n- [ ] Check queue\n"
+            )
+            .replace("### Intake\n\n", "### Intake\n\n- [ ] Triage later\n\n")
+    );
+}
+
+#[test]
+fn test_wl_task_default_shorthands() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(temp_dir.path(), SECTIONED_NOTE);
+
+    let output = run_wl_command(&["task", "Review PR", "--eng"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("### Intake\n\n### Engineering\n\n- [ ] Review PR\n\n## Notes"));
+}
+
+#[test]
+fn test_wl_shorthands_only_apply_to_commands_with_sections() {
+    let temp_dir = setup_test_env();
+    write_daily_note(temp_dir.path(), SECTIONED_NOTE);
+
+    let output = run_wl_command(&["note", "--eng", "Paired on auth"], temp_dir.path());
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'--eng'"), "Unexpected error: {}", stderr);
+    assert!(!stderr.contains("--section"));
+
+    let output = run_wl_command(&["tasks", "--support", "--color", "never"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Reply to ticket"));
+}
+
+#[test]
+fn test_wl_malformed_config_is_reported_without_panicking() {
+    let temp_dir = setup_test_env();
+    write_config(temp_dir.path(), "section_shorthands: [not, a, map\n");
+
+    let output = run_wl_command(&["--help"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Usage"));
+
+    for args in [&["tasks"][..], &["task", "--eng", "Fix build"]] {
+        let output = run_wl_command(args, temp_dir.path());
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("Failed to parse"),
+            "Unexpected error: {}",
+            stderr
+        );
+        assert!(!stderr.contains("panicked"));
+    }
+}
+
+#[test]
+fn test_wl_note_creates_missing_notes_section() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n- [ ] Existing task\n",
+    );
+
+    let output = run_wl_command(&["note", "Remember this"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        "# Today\n\n## Tasks\n\n- [ ] Existing task\n\n## Notes\n\n- Remember this\n"
+    );
+}
+
+#[test]
+fn test_wl_note_appends_after_section_content() {
+    let temp_dir = setup_test_env();
+    let original = "# Today\n\n## Notes\n\nSome context first.\n\n- Earlier note\n\n## Links\n";
+    let daily_note_path = write_daily_note(temp_dir.path(), original);
+
+    let output = run_wl_command(&["note", "Later note"], temp_dir.path());
+    assert!(output.status.success());
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        original.replace("- Earlier note\n", "- Earlier note\n- Later note\n")
+    );
+}
+
+#[test]
+fn test_wl_note_uses_configured_heading() {
+    let temp_dir = setup_test_env();
+    write_config(
+        temp_dir.path(),
+        "editor_command: nvim\nnotes_heading: Log\n",
+    );
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Log\n\n## Notes\n\n- Not here\n",
+    );
+
+    let output = run_wl_command(&["note", "Deployed v2"], temp_dir.path());
+    assert!(output.status.success());
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        "# Today\n\n## Log\n\n- Deployed v2\n\n## Notes\n\n- Not here\n"
+    );
+}
+
+#[test]
+fn test_wl_note_at_time_is_inserted_in_order() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Notes\n\n- 08:30 Coffee\n- 11:00 Design review\n",
+    );
+
+    let output = run_wl_command(&["note", "Standup", "--at", "9:15"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        "# Today\n\n## Notes\n\n- 08:30 Coffee\n- 09:15 Standup\n- 11:00 Design review\n"
+    );
+}
+
+#[test]
+fn test_wl_note_timestamps_from_config() {
+    let temp_dir = setup_test_env();
+    write_config(
+        temp_dir.path(),
+        "editor_command: nvim\nnote_timestamps: true\n",
+    );
+    let daily_note_path = write_daily_note(temp_dir.path(), "# Today\n\n## Notes\n");
+
+    run_wl_command(&["note", "Timed"], temp_dir.path());
+    run_wl_command(&["note", "Untimed", "--no-time"], temp_dir.path());
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    let timed_line = content
+        .lines()
+        .find(|line| line.ends_with(" Timed"))
+        .expect("Timed note should be written");
+    let time = timed_line
+        .strip_prefix("- ")
+        .and_then(|rest| rest.strip_suffix(" Timed"))
+        .unwrap();
+    assert!(
+        chrono::NaiveTime::parse_from_str(time, "%H:%M").is_ok(),
+        "Expected a timestamp, got {:?}",
+        timed_line
+    );
+    assert!(content.contains("\n- Untimed\n"));
+}
+
+#[test]
+fn test_wl_note_invalid_at_time() {
+    let temp_dir = setup_test_env();
+    let output = run_wl_command(&["note", "Standup", "--at", "25:00"], temp_dir.path());
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Could not understand time"));
+}
+
+#[test]
+fn test_wl_note_reads_multiline_stdin() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(temp_dir.path(), "# Today\n\n## Notes\n");
+
+    let output = run_wl_command_with_stdin(
+        &["note", "-"],
+        temp_dir.path(),
+        "commit 1a2b3c\nAuthor: Ana\n\n    Fix token refresh\n",
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        "# Today\n\n## Notes\n\n- commit 1a2b3c\n  - Author: Ana\n  - Fix token refresh\n"
+    );
+}
+
+#[test]
+fn test_wl_task_reads_subtasks_from_stdin() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(temp_dir.path(), "# Today\n\n## Tasks\n\n### Intake\n");
+
+    let output = run_wl_command_with_stdin(
+        &["task", "-"],
+        temp_dir.path(),
+        "Release 1.2\nTag the build\nPublish notes\n",
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        "# Today\n\n## Tasks\n\n### Intake\n\n- [ ] Release 1.2\n  - [ ] Tag the build\n  - [ ] Publish notes\n"
+    );
+}
+
+#[test]
+fn test_wl_note_empty_stdin_writes_nothing() {
+    let temp_dir = setup_test_env();
+    let original = "# Today\n\n## Notes\n";
+    let daily_note_path = write_daily_note(temp_dir.path(), original);
+
+    let output = run_wl_command_with_stdin(&["note", "-"], temp_dir.path(), "\n  \n");
+
+    assert!(!output.status.success());
+    assert_eq!(fs::read_to_string(&daily_note_path).unwrap(), original);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_wl_note_edit_uses_editor_buffer() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = setup_test_env();
+    let editor_path = temp_dir.path().join("fake-editor.sh");
+    fs::write(
+        &editor_path,
+        "#!/bin/sh\nprintf 'Retro notes\\nShip smaller PRs\\n' > \"$1\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&editor_path, fs::Permissions::from_mode(0o755)).unwrap();
+    write_config(
+        temp_dir.path(),
+        &format!("editor_command: {}\n", editor_path.display()),
+    );
+    let daily_note_path = write_daily_note(temp_dir.path(), "# Today\n\n## Notes\n");
+
+    let output = run_wl_command(&["note", "-e"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert_eq!(
+        content,
+        "# Today\n\n## Notes\n\n- Retro notes\n  - Ship smaller PRs\n"
+    );
+}
+
+#[test]
+fn test_wl_start_status_and_stop() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n- [ ] Fix login bug ⏱ 1h\n- [ ] Write docs\n",
+    );
+
+    let output = run_wl_command(&["start", "login"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Started: Fix login bug"));
+    assert!(
+        fs::read_to_string(&daily_note_path)
+            .unwrap()
+            .contains("- [/] Fix login bug ⏱ 1h\n")
+    );
+
+    let output = run_wl_command(&["status"], temp_dir.path());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Tracking: Fix login bug"), "{}", stdout);
+
+    let output = run_wl_command(&["stop"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(1m, 1h1m total)"));
+    assert!(
+        fs::read_to_string(&daily_note_path)
+            .unwrap()
+            .contains("- [/] Fix login bug ⏱ 1h1m\n")
+    );
+
+    let output = run_wl_command(&["status"], temp_dir.path());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No timer running"));
+    let output = run_wl_command(&["stop"], temp_dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No timer is running"));
+}
+
+#[test]
+fn test_wl_start_switches_tasks_and_keeps_the_timer_on_a_typo() {
+    let temp_dir = setup_test_env();
+    let daily_note_path = write_daily_note(
+        temp_dir.path(),
+        "# Today\n\n## Tasks\n\n- [ ] Fix login bug\n- [ ] Write docs\n",
+    );
+    run_wl_command(&["start", "login"], temp_dir.path());
+
+    let output = run_wl_command(&["start", "typo-task"], temp_dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No task matches 'typo-task'"));
+    let output = run_wl_command(&["status"], temp_dir.path());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Tracking: Fix login bug"));
+
+    let output = run_wl_command(&["start", "docs"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&daily_note_path).unwrap();
+    assert!(content.contains("- [/] Fix login bug ⏱ 1m\n- [/] Write docs\n"));
+    let output = run_wl_command(&["status"], temp_dir.path());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Tracking: Write docs"));
+}
+
+#[test]
+fn test_wl_stop_records_time_across_midnight_in_starting_note() {
+    let temp_dir = setup_test_env();
+    write_note_for(
+        temp_dir.path(),
+        "2025-01-14",
+        "# 2025-01-14\n\n## Tasks\n\n- [/] Overnight migration\n",
+    );
+    let timer = "task_id: whatever\ntask: Overnight migration\ndate: 2025-01-14\nstarted_at: 2025-01-14T23:30:00\n";
+    fs::write(temp_dir.path().join(".worklog").join("timer.yaml"), timer).unwrap();
+
+    let output = run_wl_command(&["status"], temp_dir.path());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("since 2025-01-14 23:30"));
+
+    let output = run_wl_command(&["stop"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".worklog")
+            .join("daily_notes")
+            .join("2025-01-14.md"),
+    )
+    .unwrap();
+    let tracked = content
+        .lines()
+        .find_map(|line| line.strip_prefix("- [/] Overnight migration ⏱ "))
+        .expect("Time should be recorded on the task");
+    let hours: i64 = tracked.split('h').next().unwrap().parse().unwrap();
+    assert!(hours > 24, "Expected more than a day, got {}", tracked);
+}
+
+#[test]
+fn test_wl_stop_discard() {
+    let temp_dir = setup_test_env();
+    let original = "# Today\n\n## Tasks\n\n- [/] Fix login bug\n";
+    let daily_note_path = write_daily_note(temp_dir.path(), original);
+
+    run_wl_command(&["start", "login"], temp_dir.path());
+    let output = run_wl_command(&["stop", "--discard"], temp_dir.path());
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Discarded"));
+    assert_eq!(fs::read_to_string(&daily_note_path).unwrap(), original);
+    assert!(!temp_dir.path().join(".worklog").join("timer.yaml").exists());
+}
+
+fn write_timesheet_notes(home_dir: &std::path::Path) {
+    write_note_for(
+        home_dir,
+        "2025-01-13",
+        "# 2025-01-13\n\n## Tasks\n\n### Support\n\n- [x] Reply to ticket #acme (1h30m)\n\n### Engineering\n\n- [/] Fix login bug ⏱ 45m\n- [ ] Untracked task\n",
+    );
+    write_note_for(
+        home_dir,
+        "2025-01-14",
+        "# 2025-01-14\n\n## Tasks\n\n### Engineering\n\n- [x] Fix login bug ⏱ 2h\n\n### Support\n\n- [x] Call, \"ACME\" #acme ⏱ 20m\n",
+    );
+    write_note_for(
+        home_dir,
+        "2025-01-20",
+        "# 2025-01-20\n\n## Tasks\n\n- [x] Next week (1h)\n",
+    );
+}
+
+#[test]
+fn test_wl_timesheet_week() {
+    let temp_dir = setup_test_env();
+    write_timesheet_notes(temp_dir.path());
+
+    let output = run_wl_command(
+        &["timesheet", "--week", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Timesheet 2025-01-13 to 2025-01-19\n"));
+    assert!(
+        stdout.contains("Support      1h50m\nEngineering  2h45m\n"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("#acme  1h50m\n"), "{}", stdout);
+    assert!(
+        stdout.contains("2025-01-13 Mon  2h15m\n2025-01-14 Tue  2h20m\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Fix login bug          Engineering  2h45m\n"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.trim_end().ends_with("4h35m"), "{}", stdout);
+    assert!(!stdout.contains("Untracked task"));
+    assert!(!stdout.contains("Next week"));
+}
+
+#[test]
+fn test_wl_timesheet_csv() {
+    let temp_dir = setup_test_env();
+    write_timesheet_notes(temp_dir.path());
+
+    let output = run_wl_command(
+        &[
+            "timesheet",
+            "--from",
+            "2025-01-14",
+            "--to",
+            "2025-01-20",
+            "--csv",
+        ],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "date,section,task,tags,minutes,duration\n\
+         2025-01-14,Engineering,Fix login bug,,120,2h\n\
+         2025-01-14,Support,\"Call, \"\"ACME\"\" #acme\",acme,20,20m\n\
+         2025-01-20,Tasks,Next week,,60,1h\n"
+    );
+}
+
+fn write_summary_notes(home_dir: &std::path::Path) {
+    write_note_for(
+        home_dir,
+        "2025-01-13",
+        "# 2025-01-13\n\n## Tasks\n\n### Support\n\n- [x] Reply to ticket\n\n### Engineering\n\n- [/] Fix login bug\n- [ ] Write docs\n\n## Notes\n\n- 09:00 Standup\n  - Blocked on review\n",
+    );
+    write_note_for(
+        home_dir,
+        "2025-01-15",
+        "# 2025-01-15\n\n## Tasks\n\n### Support\n\n- [x] Reply to ticket\n\n### Engineering\n\n- [x] Fix login bug ⏱ 2h\n- [ ] Write docs\n- [?] Deploy\n- [-] Dropped idea\n\n## Notes\n\n- Shipped it\n",
+    );
+}
+
+#[test]
+fn test_wl_summary_markdown() {
+    let temp_dir = setup_test_env();
+    write_summary_notes(temp_dir.path());
+
+    let output = run_wl_command(
+        &["summary", "--since", "30d", "--date", "2025-01-15"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "# Summary 2024-12-16 to 2025-01-15
+
+## Completed (2)
+
+- Reply to ticket — 2025-01-13 (Support)
+- Fix login bug — 2025-01-15 (Engineering)
+
+## Still open (2)
+
+- [ ] Write docs — carried 2 days (Engineering)
+- [?] Deploy — new (Engineering)
+
+## Sections
+
+| Section     | Completed | Open |
+| ----------- | --------: | ---: |
+| Support     | 1         | 0    |
+| Engineering | 1         | 2    |
+
+## Notes
+
+### 2025-01-13 (Monday)
+
+- 09:00 Standup
+  - Blocked on review
+
+### 2025-01-15 (Wednesday)
+
+- Shipped it
+"
+    );
+}
+
+#[test]
+fn test_wl_summary_json() {
+    let temp_dir = setup_test_env();
+    write_summary_notes(temp_dir.path());
+
+    let output = run_wl_command(
+        &["summary", "--since", "2d", "--date", "2025-01-15", "--json"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["from"], "2025-01-13");
+    assert_eq!(summary["completed"].as_array().unwrap().len(), 2);
+    assert_eq!(summary["open"][0]["content"], "Write docs");
+    assert_eq!(summary["open"][0]["first_seen"], "2025-01-13");
+    assert_eq!(summary["open"][0]["days_carried"], 2);
+    assert_eq!(summary["open"][1]["state"], "blocked");
+    assert_eq!(summary["sections"][1]["section"], "Engineering");
+    assert_eq!(summary["sections"][1]["open"], 2);
+    assert_eq!(
+        summary["notes"][0]["notes"][0],
+        "09:00 Standup\nBlocked on review"
+    );
+}
+
+fn write_standup_notes(home_dir: &std::path::Path) {
+    write_note_for(
+        home_dir,
+        "2025-01-10",
+        "# 2025-01-10\n\n## Tasks\n\n### Priority\n\n- [x] Ship **auth** fix ⏱ 2h\n- [ ] Write docs\n\n### Support\n\n- [x] Answer [ticket 42](https://example.com/42)\n",
+    );
+    write_note_for(
+        home_dir,
+        "2025-01-13",
+        "# 2025-01-13\n\n## Tasks\n\n### Priority\n\n- [ ] Write docs\n- [/] Review `parser` PR\n- [?] Deploy to staging\n\n### Support\n\n- [ ] Triage queue\n\n### Blockers\n\n- Waiting on VPN access\n",
+    );
+}
+
+#[test]
+fn test_wl_standup() {
+    let temp_dir = setup_test_env();
+    write_standup_notes(temp_dir.path());
+
+    let output = run_wl_command(&["standup", "--date", "2025-01-13"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Yesterday:
+- Ship **auth** fix
+- Answer [ticket 42](https://example.com/42)
+
+Today:
+- Write docs
+- Review `parser` PR
+
+Blockers:
+- Deploy to staging
+- Waiting on VPN access
+"
+    );
+}
+
+#[test]
+fn test_wl_standup_plain_with_configured_template() {
+    let temp_dir = setup_test_env();
+    write_standup_notes(temp_dir.path());
+    write_config(
+        temp_dir.path(),
+        "editor_command: nvim\nstandup_section: Support\nstandup_template: \"Standup {{DATE}}\\nDone: {{YESTERDAY}}\\nNext: {{TODAY}}\\nStuck: {{BLOCKERS}}\"\n",
+    );
+
+    let output = run_wl_command(
+        &["standup", "--plain", "--date", "2025-01-13"],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Standup 2025-01-13
+Done: - Ship auth fix
+- Answer ticket 42 (https://example.com/42)
+Next: - Triage queue
+Stuck: - Deploy to staging
+- Waiting on VPN access
+"
+    );
+}
+
+#[test]
+fn test_wl_standup_does_not_write_notes() {
+    let temp_dir = setup_test_env();
+    write_standup_notes(temp_dir.path());
+    write_config(temp_dir.path(), "carry_over: migrate\n");
+    let worklog_dir = temp_dir.path().join(".worklog");
+    let queue = "# Scheduled\n\n## Priority\n\n- [ ] Renew certificate ⏳ 2025-01-14\n";
+    fs::write(worklog_dir.join("scheduled.md"), queue).unwrap();
+    let previous_path = worklog_dir.join("daily_notes").join("2025-01-13.md");
+    let previous = fs::read_to_string(&previous_path).unwrap();
+
+    let output = run_wl_command(&["standup", "--date", "2025-01-14"], temp_dir.path());
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Today:\n- Write docs\n- Review `parser` PR\n"));
+
+    assert!(
+        !worklog_dir
+            .join("daily_notes")
+            .join("2025-01-14.md")
+            .exists()
+    );
+    assert_eq!(fs::read_to_string(&previous_path).unwrap(), previous);
+    assert_eq!(
+        fs::read_to_string(worklog_dir.join("scheduled.md")).unwrap(),
+        queue
+    );
+}
+
+#[test]
+fn test_wl_standup_without_previous_note() {
+    let temp_dir = setup_test_env();
+    write_daily_note(temp_dir.path(), "# Today\n\n## Tasks\n\n### Priority\n");
+
+    let output = run_wl_command(&["standup"], temp_dir.path());
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Yesterday:\n- None\n\nToday:\n- None\n\nBlockers:\n- None\n"
+    );
+}
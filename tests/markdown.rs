@@ -1,6 +1,8 @@
+use proptest::prelude::*;
 use std::fs;
 use tempfile::NamedTempFile;
 
+use worklog_cli::utils::markdown::checklist::{ChecklistItem, TaskState};
 use worklog_cli::utils::markdown::unordered_list::UnorderedListItem;
 use worklog_cli::utils::markdown::*;
 
@@ -124,14 +126,171 @@ fn test_parse_checklist() {
     match &file.blocks[0] {
         MarkdownBlock::Checklist(checklist) => {
             assert_eq!(checklist.items.len(), 3);
-            assert_eq!(checklist.items[0], (false, "Todo item".to_string()));
-            assert_eq!(checklist.items[1], (true, "Completed item".to_string()));
-            assert_eq!(checklist.items[2], (false, "Another todo".to_string()));
+            assert_eq!(checklist.items[0], item(false, "Todo item"));
+            assert_eq!(checklist.items[1], item(true, "Completed item"));
+            assert_eq!(checklist.items[2], item(false, "Another todo"));
         }
         _ => panic!("Expected checklist block"),
     }
 }
 
+#[test]
+fn test_parse_nested_checklist() {
+    let content = "- [ ] Ship feature\n  - [x] Write code\n  - [ ] Review\n    - [ ] Address comments\n- [ ] Other task\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 1);
+    match &file.blocks[0] {
+        MarkdownBlock::Checklist(checklist) => {
+            assert_eq!(checklist.items.len(), 2);
+            let parent = &checklist.items[0];
+            assert_eq!(parent.content, "Ship feature");
+            assert_eq!(parent.children.len(), 2);
+            assert_eq!(parent.children[0].state, TaskState::Done);
+            assert_eq!(parent.children[0].indentation_level, 1);
+            assert_eq!(parent.children[1].children.len(), 1);
+            assert_eq!(parent.children[1].children[0].content, "Address comments");
+            assert_eq!(parent.children[1].children[0].indentation_level, 2);
+            assert_eq!(checklist.items[1], item(false, "Other task"));
+
+            // Rendering keeps the subtask structure
+            assert_eq!(checklist.to_string(), content.trim_end());
+        }
+        _ => panic!("Expected checklist block"),
+    }
+}
+
+#[test]
+fn test_filter_completed_tasks_keeps_parents_with_open_subtasks() {
+    let content = "## Tasks\n\n- [x] Done parent\n  - [ ] Open child\n  - [x] Done child\n- [x] Fully done\n  - [x] Done child\n- [ ] Open parent\n  - [x] Done child\n";
+    let file = MarkdownFile::from_string(content).filter_completed_tasks();
+
+    assert_eq!(
+        file.to_string(),
+        "## Tasks\n\n- [x] Done parent\n  - [ ] Open child\n- [ ] Open parent\n"
+    );
+}
+
+#[test]
+fn test_plain_bullets_under_a_task_stay_with_it() {
+    let content =
+        "- [ ] Parent\n  - context link\n    - more context\n- [ ] Other\n- [x] Finished\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 1);
+    let tasks = file.tasks();
+    let texts: Vec<String> = tasks.iter().map(|(_, item)| item.text()).collect();
+    assert_eq!(texts, vec!["Parent", "Other", "Finished"]);
+    assert_eq!(
+        tasks[0].1.details,
+        vec!["  - context link", "    - more context"]
+    );
+
+    assert_eq!(
+        file.filter_completed_tasks().to_string(),
+        "- [ ] Parent\n  - context link\n    - more context\n- [ ] Other\n"
+    );
+}
+
+#[test]
+fn test_unordered_list_stops_at_tasks() {
+    let file = MarkdownFile::from_string("- plain\n- [ ] Task\n");
+
+    assert_eq!(file.blocks.len(), 2);
+    assert!(matches!(file.blocks[0], MarkdownBlock::UnorderedList(_)));
+    assert!(matches!(file.blocks[1], MarkdownBlock::Checklist(_)));
+}
+
+#[test]
+fn test_add_subtask_indents_under_parent() {
+    let mut parent = item(false, "Parent");
+    parent.add_child("Child".to_string());
+    parent.children[0].add_child("Grandchild".to_string());
+
+    let checklist = checklist::Checklist {
+        items: vec![parent],
+        source: None,
+    };
+    assert_eq!(
+        checklist.to_string(),
+        "- [ ] Parent\n  - [ ] Child\n    - [ ] Grandchild"
+    );
+}
+
+#[test]
+fn test_parse_extended_task_states() {
+    let content = "- [ ] Todo\n- [/] In progress\n- [?] Blocked\n- [x] Done\n- [-] Cancelled\n- [>] Migrated\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 1);
+    match &file.blocks[0] {
+        MarkdownBlock::Checklist(checklist) => {
+            let states: Vec<TaskState> = checklist.items.iter().map(|item| item.state).collect();
+            assert_eq!(
+                states,
+                vec![
+                    TaskState::Todo,
+                    TaskState::InProgress,
+                    TaskState::Blocked,
+                    TaskState::Done,
+                    TaskState::Cancelled,
+                    TaskState::Migrated,
+                ]
+            );
+            assert_eq!(checklist.to_string(), content.trim_end());
+        }
+        _ => panic!("Expected checklist block"),
+    }
+}
+
+#[test]
+fn test_filter_completed_tasks_applies_state_carry_over_rules() {
+    let content = "- [ ] Todo\n- [/] In progress\n- [?] Blocked\n- [x] Done\n- [-] Cancelled\n- [>] Migrated\n";
+    let file = MarkdownFile::from_string(content).filter_completed_tasks();
+
+    assert_eq!(
+        file.to_string(),
+        "- [ ] Todo\n- [/] In progress\n- [?] Blocked\n"
+    );
+}
+
+#[test]
+fn test_tasks_with_the_same_text_get_numbered_ids() {
+    let file = MarkdownFile::from_string(
+        "- [ ] Review PR\n- [ ] Other\n- [x] review  PR\n- [ ] Review PR\n",
+    );
+    let base = item(false, "Review PR").id();
+
+    let ids: Vec<String> = file
+        .tasks_with_ids()
+        .into_iter()
+        .map(|(_, _, id)| id)
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            base.clone(),
+            item(false, "Other").id(),
+            format!("{}-2", base),
+            format!("{}-3", base),
+        ]
+    );
+
+    // The same tasks carried into the next note keep their IDs
+    let carried = MarkdownFile::from_string("- [ ] Review PR\n- [ ] Other\n- [ ] review  PR\n");
+    assert_eq!(carried.tasks_with_ids()[2].2, format!("{}-2", base));
+}
+
+#[test]
+fn test_task_id_is_stable_across_state_and_whitespace() {
+    let open = item(false, "Fix  login bug");
+    let done = item(true, "fix login bug");
+
+    assert_eq!(open.id().len(), 5);
+    assert_eq!(open.id(), done.id());
+    assert_ne!(open.id(), item(false, "Fix logout bug").id());
+}
+
 #[test]
 fn test_parse_paragraph() {
     let content = "This is a simple paragraph.\n";
@@ -209,8 +368,8 @@ Another paragraph at the end.
     match &file.blocks[3] {
         MarkdownBlock::Checklist(checklist) => {
             assert_eq!(checklist.items.len(), 2);
-            assert_eq!(checklist.items[0], (false, "First task".to_string()));
-            assert_eq!(checklist.items[1], (true, "Completed task".to_string()));
+            assert_eq!(checklist.items[0], item(false, "First task"));
+            assert_eq!(checklist.items[1], item(true, "Completed task"));
         }
         _ => panic!("Expected checklist block at index 3"),
     }
@@ -346,9 +505,9 @@ fn test_checklist_with_empty_lines() {
     match &file.blocks[0] {
         MarkdownBlock::Checklist(checklist) => {
             assert_eq!(checklist.items.len(), 3);
-            assert_eq!(checklist.items[0], (false, "First task".to_string()));
-            assert_eq!(checklist.items[1], (true, "Completed task".to_string()));
-            assert_eq!(checklist.items[2], (false, "Another task".to_string()));
+            assert_eq!(checklist.items[0], item(false, "First task"));
+            assert_eq!(checklist.items[1], item(true, "Completed task"));
+            assert_eq!(checklist.items[2], item(false, "Another task"));
         }
         _ => panic!("Expected checklist block"),
     }
@@ -472,15 +631,9 @@ fn test_parse_checklist_with_uppercase_x() {
     match &file.blocks[0] {
         MarkdownBlock::Checklist(checklist) => {
             assert_eq!(checklist.items.len(), 3);
-            assert_eq!(checklist.items[0], (false, "Todo item".to_string()));
-            assert_eq!(
-                checklist.items[1],
-                (true, "Completed with uppercase X".to_string())
-            );
-            assert_eq!(
-                checklist.items[2],
-                (true, "Completed with lowercase x".to_string())
-            );
+            assert_eq!(checklist.items[0], item(false, "Todo item"));
+            assert_eq!(checklist.items[1], item(true, "Completed with uppercase X"));
+            assert_eq!(checklist.items[2], item(true, "Completed with lowercase x"));
         }
         _ => panic!("Expected checklist block"),
     }
@@ -522,6 +675,598 @@ fn test_parse_list_with_trailing_whitespace() {
     }
 }
 
+#[test]
+fn test_parse_code_block() {
+    let content = "```sql\nSELECT *\n\n  FROM users;\n- [ ] not a task\n```\nAfter the code.\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 2);
+    match &file.blocks[0] {
+        MarkdownBlock::CodeBlock(code) => {
+            assert_eq!(code.lang.as_deref(), Some("sql"));
+            assert_eq!(code.body, "SELECT *\n\n  FROM users;\n- [ ] not a task");
+        }
+        _ => panic!("Expected code block"),
+    }
+    match &file.blocks[1] {
+        MarkdownBlock::Paragraph(paragraph) => {
+            assert_eq!(paragraph.content, "After the code.");
+        }
+        _ => panic!("Expected paragraph block"),
+    }
+}
+
+#[test]
+fn test_parse_unterminated_code_block() {
+    let content = "~~~\nstack trace\n  at main\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 1);
+    match &file.blocks[0] {
+        MarkdownBlock::CodeBlock(code) => {
+            assert_eq!(code.lang, None);
+            assert_eq!(code.body, "stack trace\n  at main");
+        }
+        _ => panic!("Expected code block"),
+    }
+}
+
+#[test]
+fn test_display_code_block() {
+    let code = code_block::CodeBlock::new(Some("rust".to_string()), "fn main() {}".to_string());
+    assert_eq!(code.to_string(), "```rust\nfn main() {}\n```");
+
+    let nested = code_block::CodeBlock::new(None, "```\ninner\n```".to_string());
+    assert_eq!(nested.to_string(), "````\n```\ninner\n```\n````");
+}
+
+#[test]
+fn test_parse_block_quote() {
+    let content = "> First line\n>\n>Second line\nNot quoted\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 2);
+    match &file.blocks[0] {
+        MarkdownBlock::BlockQuote(quote) => {
+            assert_eq!(quote.lines, vec!["First line", "", "Second line"]);
+            assert_eq!(quote.to_string(), "> First line\n>\n> Second line");
+        }
+        _ => panic!("Expected block quote"),
+    }
+}
+
+#[test]
+fn test_parse_table() {
+    let content = "| Name | Hours | Billable |\n|:-----|------:|:--------:|\n| Support | 2 | yes |\n| Eng | 5 |\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 1);
+    match &file.blocks[0] {
+        MarkdownBlock::Table(table) => {
+            assert_eq!(table.header, vec!["Name", "Hours", "Billable"]);
+            assert_eq!(
+                table.alignments,
+                vec![
+                    table::Alignment::Left,
+                    table::Alignment::Right,
+                    table::Alignment::Center
+                ]
+            );
+            assert_eq!(table.rows.len(), 2);
+            assert_eq!(table.rows[0], vec!["Support", "2", "yes"]);
+            assert_eq!(table.rows[1], vec!["Eng", "5"]);
+        }
+        _ => panic!("Expected table block"),
+    }
+}
+
+#[test]
+fn test_display_table() {
+    let table = table::Table::new(
+        vec!["Task".to_string(), "Time".to_string()],
+        vec![table::Alignment::None, table::Alignment::Right],
+        vec![vec!["Fix login".to_string(), "1h".to_string()]],
+    );
+    assert_eq!(
+        table.to_string(),
+        "| Task      | Time |\n| --------- | ---: |\n| Fix login | 1h   |"
+    );
+}
+
+#[test]
+fn test_pipe_without_alignment_row_is_paragraph() {
+    let content = "a | b\nc | d\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 1);
+    assert!(matches!(file.blocks[0], MarkdownBlock::Paragraph(_)));
+}
+
+#[test]
+fn test_parse_thematic_breaks() {
+    let content = "---\n\n***\n\n- - -\n___\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 4);
+    for block in &file.blocks {
+        assert!(matches!(block, MarkdownBlock::ThematicBreak(_)));
+        assert_eq!(block.to_string(), "---");
+    }
+}
+
+#[test]
+fn test_parse_html_comment() {
+    let content =
+        "<!-- Where quick-add tasks are added\n     for later triaging -->\n- [ ] Where\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 2);
+    match &file.blocks[0] {
+        MarkdownBlock::HtmlComment(comment) => {
+            assert_eq!(
+                comment.content,
+                "Where quick-add tasks are added\nfor later triaging"
+            );
+        }
+        _ => panic!("Expected HTML comment block"),
+    }
+    assert!(matches!(file.blocks[1], MarkdownBlock::Checklist(_)));
+
+    let comment = html_comment::HtmlComment::new("todo".to_string());
+    assert_eq!(comment.to_string(), "<!-- todo -->");
+}
+
+#[test]
+fn test_paragraph_stops_at_new_block_types() {
+    let content = "Intro line\n```\ncode\n```\nMore text\n> quote\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.blocks.len(), 4);
+    assert!(matches!(file.blocks[0], MarkdownBlock::Paragraph(_)));
+    assert!(matches!(file.blocks[1], MarkdownBlock::CodeBlock(_)));
+    assert!(matches!(file.blocks[2], MarkdownBlock::Paragraph(_)));
+    assert!(matches!(file.blocks[3], MarkdownBlock::BlockQuote(_)));
+}
+
+#[test]
+fn test_round_trip_preserves_unknown_content() {
+    let content = r#"# 2025-01-15
+
+## Tasks
+
+### Intake
+
+<!-- Where quick-add tasks are added
+     for later triaging -->
+- [ ] First task
+- [X] Done with uppercase X
+
+```sql
+SELECT *
+  FROM users;
+```
+
+| Col | Other |
+|-----|-------|
+| a   | b     |
+
+## Notes
+
+  * Indented note   
+3. Numbered from three
+
+
+trailing paragraph without newline"#;
+
+    let file = MarkdownFile::from_string(content);
+    assert_eq!(file.to_string(), content);
+}
+
+#[test]
+fn test_round_trip_preserves_leading_blank_lines() {
+    let content = "\n\n  \n# Title\r\n\r\nSome text\r\n";
+    let file = MarkdownFile::from_string(content);
+    assert_eq!(file.to_string(), content);
+
+    let whitespace_only = "   \n\n  \n";
+    let file = MarkdownFile::from_string(whitespace_only);
+    assert_eq!(file.to_string(), whitespace_only);
+}
+
+#[test]
+fn test_only_mutated_blocks_are_rerendered() {
+    let content = "# Title\n\n<!-- keep   this -->\n\n## Tasks\n*  [ ] First task\n\n\n## Notes\nLine one\nline two\n";
+    let mut file = MarkdownFile::from_string(content);
+
+    match &mut file.blocks[3] {
+        MarkdownBlock::Checklist(checklist) => {
+            checklist
+                .items
+                .push(ChecklistItem::new("Second task".to_string()));
+        }
+        _ => panic!("Expected checklist block at index 3"),
+    }
+
+    assert_eq!(
+        file.to_string(),
+        "# Title\n\n<!-- keep   this -->\n\n## Tasks\n- [ ] First task\n- [ ] Second task\n\n\n## Notes\nLine one\nline two\n"
+    );
+}
+
+#[test]
+fn test_new_blocks_are_separated_by_blank_lines() {
+    let content = "# Title\n## Notes";
+    let mut file = MarkdownFile::from_string(content);
+    let mut list = unordered_list::UnorderedList::new();
+    list.items.push(UnorderedListItem {
+        content: "A note".to_string(),
+        indentation_level: 0,
+    });
+    file.blocks.push(MarkdownBlock::UnorderedList(list));
+
+    assert_eq!(file.to_string(), "# Title\n## Notes\n\n- A note\n");
+}
+
+#[test]
+fn test_move_task_between_sections() {
+    let content = "# Title\n\n## Tasks\n\n- [ ] Write report\n  - [ ] Gather numbers\n- [ ] Call Bob\n\n## Done\n\n- [x] Earlier task\n";
+    let mut file = MarkdownFile::from_string(content);
+
+    let item = file
+        .remove_task(&TaskRef {
+            block_index: 2,
+            path: vec![0],
+        })
+        .unwrap();
+    assert_eq!(item.children.len(), 1);
+
+    let task_ref = file.append_task(3, item);
+    assert_eq!(
+        task_ref,
+        TaskRef {
+            block_index: 4,
+            path: vec![1]
+        }
+    );
+    assert_eq!(
+        file.to_string(),
+        "# Title\n\n## Tasks\n\n- [ ] Call Bob\n\n## Done\n\n- [x] Earlier task\n- [ ] Write report\n  - [ ] Gather numbers\n"
+    );
+}
+
+#[test]
+fn test_removing_last_task_removes_checklist() {
+    let content = "# Title\n## Tasks\n- [ ] Only task\n## Notes\n";
+    let mut file = MarkdownFile::from_string(content);
+
+    file.remove_task(&TaskRef {
+        block_index: 2,
+        path: vec![0],
+    });
+
+    assert_eq!(file.blocks.len(), 3);
+    assert_eq!(file.to_string(), "# Title\n## Tasks\n## Notes\n");
+}
+
+#[test]
+fn test_append_task_starts_checklist_below_heading() {
+    let content = "# Title\n\n## Tasks\n\n## Notes\n";
+    let mut file = MarkdownFile::from_string(content);
+
+    let mut subtask = item(false, "Nested");
+    subtask.indentation_level = 2;
+    let task_ref = file.append_task(1, subtask);
+
+    assert_eq!(task_ref.block_index, 2);
+    assert_eq!(
+        file.to_string(),
+        "# Title\n\n## Tasks\n\n- [ ] Nested\n\n## Notes\n"
+    );
+}
+
+#[test]
+fn test_move_item_reorders_siblings() {
+    let content = "- [ ] One\n  - [ ] One A\n  - [ ] One B\n- [ ] Two\n";
+    let mut file = MarkdownFile::from_string(content);
+    let MarkdownBlock::Checklist(checklist) = &mut file.blocks[0] else {
+        panic!("Expected checklist block");
+    };
+
+    assert_eq!(checklist.move_item(&[0, 1], true), Some(vec![0, 0]));
+    assert_eq!(checklist.move_item(&[0, 0], true), None);
+    assert_eq!(checklist.move_item(&[1], false), None);
+    assert_eq!(checklist.move_item(&[0], false), Some(vec![1]));
+
+    assert_eq!(
+        file.to_string(),
+        "- [ ] Two\n- [ ] One\n  - [ ] One B\n  - [ ] One A\n"
+    );
+}
+
+#[test]
+fn test_save_writes_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("note.md");
+    let file = MarkdownFile::from_string("# Title\n\n- [ ] Task\n");
+
+    file.save(&path).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Title\n\n- [ ] Task\n"
+    );
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_find_or_insert_heading() {
+    let content = "# Title\n\n## Tasks\n\n### Support\n\n- [ ] Ticket\n\n## Notes\n";
+    let mut file = MarkdownFile::from_string(content);
+
+    assert_eq!(file.find_or_insert_heading("support", Some("Tasks")), 2);
+    assert_eq!(file.find_or_insert_heading("Engineering", Some("Tasks")), 4);
+    assert_eq!(file.find_or_insert_heading("Links", Some("Missing")), 6);
+
+    assert_eq!(
+        file.to_string(),
+        "# Title\n\n## Tasks\n\n### Support\n\n- [ ] Ticket\n\n### Engineering\n\n## Notes\n\n## Links\n"
+    );
+}
+
+#[test]
+fn test_list_item_timestamps() {
+    let time = chrono::NaiveTime::from_hms_opt(9, 5, 0).unwrap();
+    let item = UnorderedListItem::new("Standup", Some(time));
+    assert_eq!(item.content, "09:05 Standup");
+    assert_eq!(item.timestamp(), Some(time));
+    assert_eq!(item.text(), "Standup");
+
+    let plain = UnorderedListItem::new("Ratio was 3:10 today", None);
+    assert_eq!(plain.timestamp(), None);
+    assert_eq!(plain.text(), "Ratio was 3:10 today");
+
+    let not_a_time = UnorderedListItem::new("25:00 is not a time", None);
+    assert_eq!(not_a_time.timestamp(), None);
+}
+
+#[test]
+fn test_timestamped_items_are_inserted_in_order() {
+    let content = "## Notes\n\n- 09:00 Standup\n  - Blocked on review\n- 14:00 Demo\n";
+    let mut file = MarkdownFile::from_string(content);
+    let at = |h, m| Some(chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap());
+
+    file.append_list_item(
+        0,
+        UnorderedListItem::new("Lunch", at(12, 30)),
+        vec![UnorderedListItem::new("Talked about roadmap", None)],
+    );
+    file.append_list_item(0, UnorderedListItem::new("Late", at(17, 0)), Vec::new());
+    file.append_list_item(0, UnorderedListItem::new("Untimed", None), Vec::new());
+
+    assert_eq!(
+        file.to_string(),
+        "## Notes\n\n- 09:00 Standup\n  - Blocked on review\n- 12:30 Lunch\n  - Talked about roadmap\n- 14:00 Demo\n- 17:00 Late\n- Untimed\n"
+    );
+}
+
+#[test]
+fn test_tracked_time_annotations() {
+    let mut task = item(false, "Fix login bug");
+    let id = task.id();
+    assert_eq!(task.tracked_time(), None);
+
+    task.add_tracked_time(chrono::Duration::minutes(45));
+    assert_eq!(task.content, "Fix login bug ⏱ 45m");
+    task.add_tracked_time(chrono::Duration::minutes(35));
+    assert_eq!(task.content, "Fix login bug ⏱ 1h20m");
+
+    assert_eq!(task.tracked_time(), Some(chrono::Duration::minutes(80)));
+    assert_eq!(task.text(), "Fix login bug");
+    assert_eq!(task.id(), id);
+}
+
+#[test]
+fn test_carry_over_drops_tracked_time() {
+    let content = "# 2025-01-14\n\n- [/] Fix login bug ⏱ 2h\n- [x] Done task ⏱ 1h\n";
+    let file = MarkdownFile::from_string(content);
+
+    assert_eq!(
+        file.filter_completed_tasks().to_string(),
+        "# 2025-01-14\n\n- [/] Fix login bug\n"
+    );
+}
+
+#[test]
+fn test_duration_annotations_and_tags() {
+    let task = item(true, "Fix login bug #auth (1h30m) ⏱ 45m");
+    assert_eq!(task.tracked_time(), Some(chrono::Duration::minutes(135)));
+    assert_eq!(task.text(), "Fix login bug #auth");
+    assert_eq!(task.tags(), vec!["auth".to_string()]);
+
+    let plain = item(false, "Call Bob (about lunch)");
+    assert_eq!(plain.tracked_time(), None);
+    assert_eq!(plain.text(), "Call Bob (about lunch)");
+    assert!(plain.tags().is_empty());
+
+    let mut tracked = item(false, "Review PR (30m)");
+    tracked.add_tracked_time(chrono::Duration::minutes(15));
+    assert_eq!(tracked.content, "Review PR ⏱ 45m");
+}
+
+#[test]
+fn test_since_annotation_is_kept_out_of_content() {
+    let content = "- [ ] Fix login bug ⏱ 1h <!-- since:2025-01-10 -->\n  - [ ] Write test\n";
+    let file = MarkdownFile::from_string(content);
+    let tasks = file.tasks();
+
+    let (_, task) = tasks[0];
+    assert_eq!(task.content, "Fix login bug ⏱ 1h");
+    assert_eq!(task.since, chrono::NaiveDate::from_ymd_opt(2025, 1, 10));
+    assert_eq!(task.id(), item(false, "Fix login bug").id());
+    assert_eq!(tasks[1].1.since, None);
+    assert_eq!(file.to_string(), content);
+}
+
+#[test]
+fn test_due_and_scheduled_dates_are_parsed() {
+    let date = |s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    let file = MarkdownFile::from_string(
+        "- [ ] Send report 📅 2025-02-01\n- [ ] Pay invoice due:2025-02-03 #finance\n- [ ] Renew cert ⏳ 2025-02-10\n- [ ] Plain\n",
+    );
+    let tasks = file.tasks();
+
+    assert_eq!(tasks[0].1.due(), Some(date("2025-02-01")));
+    assert_eq!(tasks[0].1.text(), "Send report");
+    assert_eq!(tasks[0].1.id(), item(false, "Send report").id());
+    assert_eq!(tasks[1].1.due(), Some(date("2025-02-03")));
+    assert_eq!(tasks[1].1.text(), "Pay invoice #finance");
+    assert_eq!(tasks[2].1.scheduled(), Some(date("2025-02-10")));
+    assert_eq!(tasks[2].1.due(), None);
+    assert_eq!(tasks[2].1.text(), "Renew cert");
+    assert_eq!(tasks[3].1.due(), None);
+    assert_eq!(tasks[3].1.scheduled(), None);
+}
+
+#[test]
+fn test_set_due_and_scheduled_dates() {
+    let date = |s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    let mut task = item(false, "Send report");
+    task.set_due(date("2025-02-01"));
+    task.set_scheduled(Some(date("2025-01-20")));
+    assert_eq!(task.content, "Send report 📅 2025-02-01 ⏳ 2025-01-20");
+
+    task.set_scheduled(None);
+    assert_eq!(task.content, "Send report 📅 2025-02-01");
+    assert_eq!(task.due(), Some(date("2025-02-01")));
+}
+
+#[test]
+fn test_mark_tasks_since_keeps_existing_dates() {
+    let content = "# 2025-01-14\n\n- [ ] Old task <!-- since:2025-01-10 -->\n- [ ] New task\n  - [ ] Subtask\n";
+    let mut file = MarkdownFile::from_string(content).filter_completed_tasks();
+    file.mark_tasks_since(chrono::NaiveDate::from_ymd_opt(2025, 1, 14).unwrap());
+
+    assert_eq!(
+        file.to_string(),
+        "# 2025-01-14\n\n- [ ] Old task <!-- since:2025-01-10 -->\n- [ ] New task <!-- since:2025-01-14 -->\n  - [ ] Subtask <!-- since:2025-01-14 -->\n"
+    );
+}
+
+#[test]
+fn test_migrate_open_tasks_marks_them_migrated() {
+    let mut file = MarkdownFile::from_string(
+        "- [ ] Todo\n- [x] Done\n  - [/] Open subtask\n- [?] Blocked\n- [-] Cancelled\n",
+    );
+
+    assert_eq!(file.migrate_open_tasks(), 3);
+    assert_eq!(
+        file.to_string(),
+        "- [>] Todo\n- [x] Done\n  - [>] Open subtask\n- [>] Blocked\n- [-] Cancelled\n"
+    );
+}
+
+#[test]
+fn test_merge_tasks_places_tasks_under_matching_headings() {
+    let mut today = MarkdownFile::from_string(
+        "# 2025-01-15\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n\n## Notes\n",
+    );
+    let previous = MarkdownFile::from_string(
+        "# 2025-01-14\n\n- [ ] Loose task\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n- [ ] Call vendor\n  - [ ] Find number\n\n### Hiring\n\n- [ ] Review CVs\n",
+    );
+
+    let added = today.merge_tasks(&previous).headings;
+
+    assert_eq!(added, vec!["Hiring".to_string()]);
+    assert_eq!(
+        today.to_string(),
+        "# 2025-01-15\n\n- [ ] Loose task\n\n## Tasks\n\n### Support\n\n- [ ] Reply to ticket\n- [ ] Call vendor\n  - [ ] Find number\n\n### Hiring\n\n- [ ] Review CVs\n\n## Notes\n"
+    );
+}
+
+#[test]
+fn test_merge_tasks_only_skips_tasks_already_under_the_same_heading() {
+    let mut today = MarkdownFile::from_string(
+        "# 2025-01-15\n\n## Tasks\n\n### Support\n\n- [ ] Write docs\n\n### Engineering\n",
+    );
+    let previous = MarkdownFile::from_string(
+        "# 2025-01-14\n\n## Tasks\n\n### Support\n\n- [ ] Write docs\n- [x] Done\n\n### Engineering\n\n- [ ] Write docs\n- [ ] Write docs\n",
+    );
+
+    let merged = today.merge_tasks(&previous);
+
+    assert_eq!(merged.tasks.len(), 2);
+    assert_eq!(merged.already_open.len(), 1);
+    assert!(merged.headings.is_empty());
+    assert_eq!(
+        today.to_string(),
+        "# 2025-01-15\n\n## Tasks\n\n### Support\n\n- [ ] Write docs\n\n### Engineering\n\n- [ ] Write docs\n- [ ] Write docs\n"
+    );
+}
+
+fn markdown_line() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::new()),
+        Just("   ".to_string()),
+        "#{1,7} ?[a-zA-Z ]{0,10}",
+        "[ \t]{0,4}[-*+] \\[[ xX]\\] ?[a-zA-Z ]{0,10}",
+        "[ \t]{0,4}[-*+] ?[a-zA-Z ]{0,10}",
+        "[0-9]{1,2}\\. ?[a-zA-Z ]{0,10}",
+        "<!-- [a-z ]{0,10}( -->)?",
+        "(```|~~~)[a-z]{0,4}",
+        "> ?[a-zA-Z ]{0,10}",
+        "[-*_]( ?[-*_]){2,3}",
+        "\\|[a-z |:-]{0,12}",
+        "[ \t]{0,2}[a-zA-Z .,]{1,20}[ \t]{0,2}",
+        "\\PC{0,12}",
+    ]
+}
+
+fn markdown_document() -> impl Strategy<Value = String> {
+    (
+        prop::collection::vec((markdown_line(), prop_oneof!["\n", "\r\n"]), 0..20),
+        any::<bool>(),
+    )
+        .prop_map(|(lines, trailing_newline)| {
+            let mut document: String = lines
+                .into_iter()
+                .map(|(line, ending)| line + &ending)
+                .collect();
+            if !trailing_newline && document.ends_with('\n') {
+                document.pop();
+            }
+            document
+        })
+}
+
+proptest! {
+    #[test]
+    fn prop_round_trip_unmodified_file(content in markdown_document()) {
+        let file = MarkdownFile::from_string(&content);
+        prop_assert_eq!(file.to_string(), content);
+    }
+
+    #[test]
+    fn prop_round_trip_arbitrary_text(content in "\\PC*") {
+        let file = MarkdownFile::from_string(&content);
+        prop_assert_eq!(file.to_string(), content);
+    }
+}
+
+fn item(checked: bool, content: &str) -> ChecklistItem {
+    ChecklistItem {
+        state: if checked {
+            TaskState::Done
+        } else {
+            TaskState::Todo
+        },
+        content: content.to_string(),
+        indentation_level: 0,
+        since: None,
+        details: Vec::new(),
+        children: Vec::new(),
+    }
+}
+
 fn create_temp_file(content: &str) -> NamedTempFile {
     let temp_file = NamedTempFile::new().expect("Failed to create temp file");
     fs::write(temp_file.path(), content).expect("Failed to write to temp file");
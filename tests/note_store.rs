@@ -0,0 +1,87 @@
+use std::fs;
+
+use chrono::NaiveDate;
+use tempfile::TempDir;
+
+use worklog_cli::note_store::NoteStore;
+
+fn date(input: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(input, "%Y-%m-%d").unwrap()
+}
+
+fn store_with(files: &[&str]) -> (NoteStore, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    for file in files {
+        fs::write(temp_dir.path().join(file), "# Note\n").unwrap();
+    }
+    (NoteStore::new(temp_dir.path().to_path_buf()), temp_dir)
+}
+
+#[test]
+fn test_dates_only_include_daily_note_files() {
+    let (store, temp_dir) = store_with(&[
+        "2025-01-15.md",
+        "2025-01-10.md",
+        "notes.md",
+        "2025-01-16.backup.md",
+        "2025-1-17.md",
+        "2025-01-18.txt",
+    ]);
+    fs::create_dir(temp_dir.path().join("2025-01-19.md")).unwrap();
+
+    assert_eq!(
+        store.dates().unwrap(),
+        vec![date("2025-01-10"), date("2025-01-15")]
+    );
+}
+
+#[test]
+fn test_latest_before_is_strictly_before_and_skips_gaps() {
+    let (store, _temp_dir) = store_with(&["2025-01-06.md", "2025-01-10.md", "2025-01-15.md"]);
+
+    let (latest, path) = store.latest_before(date("2025-01-15")).unwrap().unwrap();
+    assert_eq!(latest, date("2025-01-10"));
+    assert_eq!(path, store.path_for(date("2025-01-10")));
+
+    let (latest, _) = store.latest_before(date("2025-01-09")).unwrap().unwrap();
+    assert_eq!(latest, date("2025-01-06"));
+
+    assert_eq!(store.latest_before(date("2025-01-06")).unwrap(), None);
+}
+
+#[test]
+fn test_notes_between_includes_both_ends() {
+    let (store, _temp_dir) = store_with(&[
+        "2025-01-05.md",
+        "2025-01-06.md",
+        "2025-01-10.md",
+        "2025-01-12.md",
+        "2025-01-13.md",
+    ]);
+
+    let dates: Vec<_> = store
+        .notes_between(date("2025-01-06"), date("2025-01-12"))
+        .unwrap()
+        .into_iter()
+        .map(|(note_date, _)| note_date)
+        .collect();
+    assert_eq!(
+        dates,
+        vec![date("2025-01-06"), date("2025-01-10"), date("2025-01-12")]
+    );
+}
+
+#[test]
+fn test_missing_directory_has_no_notes() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = NoteStore::new(temp_dir.path().join("daily_notes"));
+
+    assert!(store.dates().unwrap().is_empty());
+    assert_eq!(store.latest_before(date("2025-01-15")).unwrap(), None);
+    assert!(
+        store
+            .notes_between(date("2025-01-01"), date("2025-01-31"))
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,75 @@
+use chrono::{NaiveDate, Weekday};
+
+use worklog_cli::utils::schedule::Schedule;
+
+fn date(input: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(input, "%Y-%m-%d").unwrap()
+}
+
+fn matching_days(schedule: &str, from: &str, to: &str) -> Vec<String> {
+    let schedule: Schedule = schedule.parse().unwrap();
+    date(from)
+        .iter_days()
+        .take_while(|day| *day <= date(to))
+        .filter(|day| schedule.matches(*day))
+        .map(|day| day.to_string())
+        .collect()
+}
+
+#[test]
+fn test_parse_schedules() {
+    assert_eq!("daily".parse(), Ok(Schedule::Daily));
+    assert_eq!("Every weekday".parse(), Ok(Schedule::Workdays));
+    assert_eq!(
+        "mon, Thu".parse(),
+        Ok(Schedule::Weekdays(vec![Weekday::Mon, Weekday::Thu]))
+    );
+    assert_eq!("1st of month".parse(), Ok(Schedule::DayOfMonth(1)));
+    assert_eq!("15th of the month".parse(), Ok(Schedule::DayOfMonth(15)));
+    assert_eq!("2025-12-24".parse(), Ok(Schedule::Date(date("2025-12-24"))));
+    assert!("32nd of month".parse::<Schedule>().is_err());
+    assert!(
+        "someday"
+            .parse::<Schedule>()
+            .unwrap_err()
+            .starts_with("Unknown schedule 'someday'")
+    );
+}
+
+#[test]
+fn test_weekday_schedules() {
+    // 2025-06-02 is a Monday
+    assert_eq!(
+        matching_days("mon,thu", "2025-06-01", "2025-06-08"),
+        vec!["2025-06-02", "2025-06-05"]
+    );
+    assert_eq!(
+        matching_days("weekend", "2025-06-01", "2025-06-08"),
+        vec!["2025-06-01", "2025-06-07", "2025-06-08"]
+    );
+    assert_eq!(
+        matching_days("workday", "2025-06-01", "2025-06-08").len(),
+        5
+    );
+}
+
+#[test]
+fn test_monthly_schedules() {
+    // June 2025 starts on a Sunday and ends on a Monday; November 2025 ends on a Sunday
+    assert_eq!(
+        matching_days("first workday of month", "2025-05-25", "2025-06-30"),
+        vec!["2025-06-02"]
+    );
+    assert_eq!(
+        matching_days("last workday of month", "2025-11-01", "2025-12-01"),
+        vec!["2025-11-28"]
+    );
+    assert_eq!(
+        matching_days("31st of month", "2025-06-01", "2025-07-31"),
+        vec!["2025-06-30", "2025-07-31"]
+    );
+    assert_eq!(
+        matching_days("1st of month", "2025-06-01", "2025-07-31"),
+        vec!["2025-06-01", "2025-07-01"]
+    );
+}
@@ -0,0 +1,74 @@
+use chrono::NaiveDate;
+
+use worklog_cli::utils::template::{TemplateContext, render_template};
+
+fn context(date: &str) -> TemplateContext {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+    let mut context = TemplateContext::new(date, NaiveDate::from_ymd_opt(2025, 1, 16));
+    context.user = "sam".to_string();
+    context
+        .custom
+        .insert("team".to_string(), "Platform".to_string());
+    context
+}
+
+#[test]
+fn test_variables_and_date_formats() {
+    let rendered = render_template(
+        "# {{DATE}} ({{WEEKDAY}}, week {{WEEK_NUMBER}})\n\n{{ DATE | %A, %B %d }} for {{USER}} on {{team}}\nPrevious: {{YESTERDAY_LINK}}, a {{YESTERDAY | %a}}\n",
+        &context("2025-01-17"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        rendered,
+        "# 2025-01-17 (Friday, week 3)\n\nFriday, January 17 for sam on Platform\nPrevious: [2025-01-16](2025-01-16.md), a Thu\n"
+    );
+}
+
+#[test]
+fn test_conditionals_drop_their_lines() {
+    let template = "## Tasks\n\n{{#if friday}}\n### Retro\n\n{{else}}\n### Focus\n\n{{/if}}\n{{#if not weekend}}\n### Standup\n{{/if}}\n## Notes\n";
+
+    assert_eq!(
+        render_template(template, &context("2025-01-17")).unwrap(),
+        "## Tasks\n\n### Retro\n\n### Standup\n## Notes\n"
+    );
+    assert_eq!(
+        render_template(template, &context("2025-01-18")).unwrap(),
+        "## Tasks\n\n### Focus\n\n## Notes\n"
+    );
+    assert_eq!(
+        render_template(
+            "Today{{#if mon}} (planning){{/if}}.",
+            &context("2025-01-13")
+        )
+        .unwrap(),
+        "Today (planning)."
+    );
+}
+
+#[test]
+fn test_template_errors() {
+    let context = context("2025-01-17");
+    let error = |template: &str| render_template(template, &context).unwrap_err();
+
+    assert_eq!(error("# {{DAET}}"), "Unknown template variable '{{DAET}}'");
+    assert_eq!(
+        error("{{team | %A}}"),
+        "Only dates can be formatted, but '{{team | %A}}' is not a date"
+    );
+    assert_eq!(
+        error("{{DATE | %Q}}"),
+        "Invalid date format in '{{DATE | %Q}}'"
+    );
+    assert!(error("{{#if someday}}x{{/if}}").starts_with("Unknown template condition 'someday'"));
+    assert_eq!(
+        error("{{#if friday}}\nRetro\n"),
+        "Template has {{#if friday}} without a closing {{/if}}"
+    );
+    assert_eq!(
+        error("{{/if}}"),
+        "Template has {{/if}} without a matching {{#if}}"
+    );
+}
@@ -0,0 +1,154 @@
+use chrono::{Duration, NaiveDate, NaiveTime};
+
+use worklog_cli::utils::time::{
+    format_duration, month_bounds, parse_duration, parse_time_arg, resolve_date,
+    resolve_future_date, resolve_since, week_bounds,
+};
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+// 2025-01-15 is a Wednesday
+fn today() -> NaiveDate {
+    date("2025-01-15")
+}
+
+#[test]
+fn test_resolve_iso_date() {
+    assert_eq!(resolve_date("2024-12-31", today()), Ok(date("2024-12-31")));
+}
+
+#[test]
+fn test_resolve_named_days() {
+    assert_eq!(resolve_date("today", today()), Ok(date("2025-01-15")));
+    assert_eq!(resolve_date("yesterday", today()), Ok(date("2025-01-14")));
+    assert_eq!(resolve_date("Tomorrow", today()), Ok(date("2025-01-16")));
+}
+
+#[test]
+fn test_resolve_offsets() {
+    assert_eq!(resolve_date("-3d", today()), Ok(date("2025-01-12")));
+    assert_eq!(resolve_date("+2d", today()), Ok(date("2025-01-17")));
+    assert_eq!(resolve_date("-1w", today()), Ok(date("2025-01-08")));
+    assert_eq!(resolve_date("3 days ago", today()), Ok(date("2025-01-12")));
+}
+
+#[test]
+fn test_resolve_weekdays() {
+    assert_eq!(resolve_date("friday", today()), Ok(date("2025-01-10")));
+    assert_eq!(resolve_date("wednesday", today()), Ok(date("2025-01-15")));
+    assert_eq!(resolve_date("last friday", today()), Ok(date("2025-01-10")));
+    assert_eq!(
+        resolve_date("last wednesday", today()),
+        Ok(date("2025-01-08"))
+    );
+    assert_eq!(resolve_date("next monday", today()), Ok(date("2025-01-20")));
+    assert_eq!(
+        resolve_date("next wednesday", today()),
+        Ok(date("2025-01-22"))
+    );
+}
+
+#[test]
+fn test_resolve_invalid_date() {
+    assert!(resolve_date("someday", today()).is_err());
+    assert!(resolve_date("2025-13-01", today()).is_err());
+    assert!(resolve_date("last blursday", today()).is_err());
+    assert!(resolve_date("-99999999999d", today()).is_err());
+    assert!(resolve_date("+9999999999999999w", today()).is_err());
+    assert!(resolve_since("99999999999d", today()).is_err());
+}
+
+#[test]
+fn test_resolve_since() {
+    assert_eq!(resolve_since("7d", today()), Ok(date("2025-01-08")));
+    assert_eq!(resolve_since("2w", today()), Ok(date("2025-01-01")));
+    assert_eq!(resolve_since("2025-01-02", today()), Ok(date("2025-01-02")));
+    assert_eq!(
+        resolve_since("last friday", today()),
+        Ok(date("2025-01-10"))
+    );
+}
+
+#[test]
+fn test_resolve_future_date() {
+    assert_eq!(
+        resolve_future_date("friday", today()),
+        Ok(date("2025-01-17"))
+    );
+    assert_eq!(
+        resolve_future_date("wednesday", today()),
+        Ok(date("2025-01-15"))
+    );
+    assert_eq!(
+        resolve_future_date("monday", today()),
+        Ok(date("2025-01-20"))
+    );
+    assert_eq!(resolve_future_date("+3d", today()), Ok(date("2025-01-18")));
+    assert_eq!(
+        resolve_future_date("last friday", today()),
+        Ok(date("2025-01-10"))
+    );
+    assert!(resolve_future_date("someday", today()).is_err());
+}
+
+#[test]
+fn test_parse_time() {
+    let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    assert_eq!(parse_time_arg("09:15"), Ok(time(9, 15)));
+    assert_eq!(parse_time_arg("9:15"), Ok(time(9, 15)));
+    assert_eq!(parse_time_arg("14:32"), Ok(time(14, 32)));
+    assert_eq!(parse_time_arg("9:15am"), Ok(time(9, 15)));
+    assert_eq!(parse_time_arg("2:30 PM"), Ok(time(14, 30)));
+    assert_eq!(parse_time_arg("12am"), Ok(time(0, 0)));
+    assert_eq!(parse_time_arg("12pm"), Ok(time(12, 0)));
+}
+
+#[test]
+fn test_parse_invalid_time() {
+    assert!(parse_time_arg("25:00").is_err());
+    assert!(parse_time_arg("9:5:1").is_err());
+    assert!(parse_time_arg("13pm").is_err());
+    assert!(parse_time_arg("noon").is_err());
+}
+
+#[test]
+fn test_format_and_parse_durations() {
+    assert_eq!(format_duration(Duration::minutes(80)), "1h20m");
+    assert_eq!(format_duration(Duration::minutes(45)), "45m");
+    assert_eq!(format_duration(Duration::minutes(120)), "2h");
+    assert_eq!(format_duration(Duration::seconds(59)), "0m");
+
+    assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+    assert_eq!(parse_duration("45m"), Some(Duration::minutes(45)));
+    assert_eq!(parse_duration("2h"), Some(Duration::minutes(120)));
+    assert_eq!(parse_duration("2H5M"), Some(Duration::minutes(125)));
+    assert_eq!(parse_duration("h30m"), None);
+    assert_eq!(parse_duration("1h30"), Some(Duration::minutes(90)));
+    assert_eq!(parse_duration("m"), None);
+    assert_eq!(parse_duration("90"), None);
+    assert_eq!(parse_duration("soon"), None);
+    assert_eq!(parse_duration("99999999999999999h"), None);
+    assert_eq!(parse_duration("99999999999999999m"), None);
+}
+
+#[test]
+fn test_week_and_month_bounds() {
+    assert_eq!(
+        week_bounds(today()),
+        (date("2025-01-13"), date("2025-01-19"))
+    );
+    assert_eq!(
+        week_bounds(date("2025-01-19")),
+        (date("2025-01-13"), date("2025-01-19"))
+    );
+    assert_eq!(
+        month_bounds(date("2024-02-10")),
+        (date("2024-02-01"), date("2024-02-29"))
+    );
+    assert_eq!(
+        month_bounds(date("2024-12-31")),
+        (date("2024-12-01"), date("2024-12-31"))
+    );
+}
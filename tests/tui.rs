@@ -0,0 +1,153 @@
+use std::{fs, path::PathBuf};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tempfile::TempDir;
+
+use worklog_cli::commands::tui::{App, Mode, Row};
+use worklog_cli::utils::markdown::MarkdownFile;
+
+const NOTE: &str = "# 2025-01-15\n\n## Tasks\n\n- [ ] Write report\n- [ ] Call Bob\n\n## Later\n\n- [ ] Plan trip\n";
+
+fn app_for(content: &str) -> (App, TempDir, PathBuf) {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("2025-01-15.md");
+    fs::write(&path, content).unwrap();
+    let app = App::new(MarkdownFile::from_string(content), path.clone());
+    (app, temp_dir, path)
+}
+
+fn press(app: &mut App, keys: &[KeyCode]) {
+    for key in keys {
+        app.handle_key(KeyEvent::new(*key, KeyModifiers::NONE));
+    }
+}
+
+fn selected_content(app: &App) -> &str {
+    match &app.rows[app.cursor] {
+        Row::Task { content, .. } => content,
+        Row::Heading { content, .. } => content,
+    }
+}
+
+#[test]
+fn test_starts_on_first_task() {
+    let (app, _temp_dir, _) = app_for(NOTE);
+    assert_eq!(selected_content(&app), "Write report");
+}
+
+#[test]
+fn test_toggle_and_save() {
+    let (mut app, _temp_dir, path) = app_for(NOTE);
+
+    press(&mut app, &[KeyCode::Char(' ')]);
+    assert!(app.modified);
+    press(&mut app, &[KeyCode::Char('s')]);
+
+    assert!(!app.modified);
+    assert!(
+        fs::read_to_string(&path)
+            .unwrap()
+            .contains("- [x] Write report\n- [ ] Call Bob")
+    );
+}
+
+#[test]
+fn test_reorder_keeps_selection() {
+    let (mut app, _temp_dir, _) = app_for(NOTE);
+
+    press(&mut app, &[KeyCode::Char('J')]);
+
+    assert_eq!(selected_content(&app), "Write report");
+    assert!(
+        app.note
+            .to_string()
+            .contains("- [ ] Call Bob\n- [ ] Write report")
+    );
+}
+
+#[test]
+fn test_move_to_other_section() {
+    let (mut app, _temp_dir, _) = app_for(NOTE);
+
+    press(&mut app, &[KeyCode::Char('m')]);
+    assert_eq!(app.mode, Mode::Move { target: 1 });
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter]);
+
+    assert_eq!(selected_content(&app), "Write report");
+    assert_eq!(
+        app.note.to_string(),
+        "# 2025-01-15\n\n## Tasks\n\n- [ ] Call Bob\n\n## Later\n\n- [ ] Plan trip\n- [ ] Write report\n"
+    );
+}
+
+#[test]
+fn test_edit_add_and_delete() {
+    let (mut app, _temp_dir, _) = app_for(NOTE);
+
+    press(
+        &mut app,
+        &[KeyCode::Char('e'), KeyCode::Char('x'), KeyCode::Backspace],
+    );
+    press(&mut app, &[KeyCode::Char('s'), KeyCode::Enter]);
+    assert_eq!(selected_content(&app), "Write reports");
+
+    press(&mut app, &[KeyCode::Char('a')]);
+    for c in "Review PR".chars() {
+        press(&mut app, &[KeyCode::Char(c)]);
+    }
+    press(&mut app, &[KeyCode::Enter]);
+    assert_eq!(selected_content(&app), "Review PR");
+
+    press(&mut app, &[KeyCode::Char('d'), KeyCode::Char('y')]);
+
+    assert_eq!(
+        app.note.to_string(),
+        "# 2025-01-15\n\n## Tasks\n\n- [ ] Write reports\n- [ ] Call Bob\n\n## Later\n\n- [ ] Plan trip\n"
+    );
+}
+
+#[test]
+fn test_quit_asks_to_save_changes() {
+    let (mut app, _temp_dir, path) = app_for(NOTE);
+
+    press(&mut app, &[KeyCode::Char('x'), KeyCode::Char('q')]);
+    assert_eq!(app.mode, Mode::ConfirmQuit);
+    assert!(!app.should_quit);
+
+    press(&mut app, &[KeyCode::Char('n')]);
+    assert!(app.should_quit);
+    assert_eq!(fs::read_to_string(&path).unwrap(), NOTE);
+}
+
+#[test]
+fn test_failed_save_on_quit_shows_the_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("missing").join("2025-01-15.md");
+    let mut app = App::new(MarkdownFile::from_string(NOTE), path);
+
+    press(
+        &mut app,
+        &[KeyCode::Char('x'), KeyCode::Char('q'), KeyCode::Char('y')],
+    );
+    assert!(!app.should_quit);
+    assert_eq!(app.mode, Mode::Normal);
+    assert!(app.status.starts_with("Failed to save daily note"));
+}
+
+#[test]
+fn test_flags_stale_open_tasks() {
+    let content = "# 2025-01-15\n\n## Tasks\n\n- [ ] Write report <!-- since:2025-01-06 -->\n- [x] Call Bob <!-- since:2025-01-06 -->\n- [ ] Plan trip <!-- since:2025-01-14 -->\n- [ ] Book flights\n";
+    let (app, _temp_dir, _) = app_for(content);
+    let app = app.with_stale_before(chrono::NaiveDate::from_ymd_opt(2025, 1, 10).unwrap());
+
+    let stale: Vec<bool> = app
+        .rows
+        .iter()
+        .filter_map(|row| match row {
+            Row::Task { stale, .. } => Some(*stale),
+            Row::Heading { .. } => None,
+        })
+        .collect();
+    assert_eq!(stale, vec![true, false, false, false]);
+    assert_eq!(selected_content(&app), "Write report");
+}